@@ -0,0 +1,88 @@
+//! Compares rendering frames with BMP decoding against the tile cache.
+//!
+//! Run with `cargo bench --bench tile_cache` in `spooky-core`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics_framebuf::FrameBuf;
+use spooky_core::heapbuffer::HeapBuffer;
+use spooky_core::maze::Maze;
+use spooky_core::render::pixel_format::Direct;
+use spooky_core::render::tile_cache::TileCache;
+use spooky_core::render::zoom::Zoom;
+use spooky_core::render::{render_frame, render_frame_cached};
+use spooky_core::rng::{GameRng, Stream};
+use spooky_core::systems::hud::HudState;
+use spooky_core::systems::setup::TextureAssets;
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 240;
+const PIXELS: usize = WIDTH * HEIGHT;
+const FRAMES: u32 = 200;
+
+type Screen = FrameBuf<Rgb565, HeapBuffer<Rgb565, PIXELS>>;
+
+fn screen() -> Screen {
+    FrameBuf::new(
+        HeapBuffer::new(Box::new([Rgb565::BLACK; PIXELS])),
+        WIDTH,
+        HEIGHT,
+    )
+}
+
+/// Render `FRAMES` frames while the camera scrolls by one pixel per frame.
+fn run(mut render: impl FnMut(Point)) -> Duration {
+    let start = Instant::now();
+    for frame in 0..FRAMES as i32 {
+        render(Point::new(11 * 16 + frame % 64, 10 * 16 + frame % 32));
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let mut rng = GameRng::new([7; 32]);
+    let mut maze = Maze::new(64, 64);
+    maze.generate_maze(32, 32, rng.stream(Stream::Layout));
+    maze.generate_coins(rng.stream(Stream::Items));
+    let textures = TextureAssets::load();
+    let tile_cache = TileCache::new(&textures, Zoom::Normal, &Direct);
+    let hud = HudState::default();
+
+    let mut frame = screen();
+    let uncached = run(|camera| {
+        let summary =
+            render_frame(&mut frame, &maze, &textures, camera, Zoom::Normal, [], &hud).unwrap();
+        black_box(summary);
+    });
+
+    let mut cached_frame = screen();
+    let cached = run(|camera| {
+        let summary =
+            render_frame_cached(&mut cached_frame, &tile_cache, &maze, camera, [], &hud).unwrap();
+        black_box(summary);
+    });
+
+    // Both paths must produce the same picture.
+    assert!(frame.data[..] == cached_frame.data[..]);
+
+    // The scaled tile sets must match the zoomed uncached renderer as well.
+    for zoom in [Zoom::Half, Zoom::Double] {
+        let tile_cache = TileCache::new(&textures, zoom, &Direct);
+        let camera = Point::new(37, 53);
+        render_frame(&mut frame, &maze, &textures, camera, zoom, [], &hud).unwrap();
+        render_frame_cached(&mut cached_frame, &tile_cache, &maze, camera, [], &hud).unwrap();
+        assert!(frame.data[..] == cached_frame.data[..], "{:?}", zoom);
+    }
+
+    let per_frame = |total: Duration| total / FRAMES;
+    println!("{} frames of {}x{}", FRAMES, WIDTH, HEIGHT);
+    println!("bmp decode:  {:?} per frame", per_frame(uncached));
+    println!("tile cache:  {:?} per frame", per_frame(cached));
+    println!(
+        "speed-up:    {:.1}x",
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+}
@@ -0,0 +1,365 @@
+//! Board abstraction.
+//!
+//! A target implements `Board` for its display, sensors, entropy source and clock;
+//! `run_game` builds the Bevy app around it and drives the main loop. Everything else
+//! (game systems, rendering into a framebuffer, dirty-region flushing, tilt and touch
+//! handling) is shared. The frame is rendered in the display's own colour; the
+//! board's `Palette` converts the textures when the tile cache decodes them.
+
+use core::fmt::Debug;
+use core::time::Duration;
+
+use bevy::app::TaskPoolPlugin;
+use bevy::prelude::*;
+use bevy::time::{TimePlugin, TimeUpdateStrategy};
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::{DrawTarget, PixelColor, Point, RgbColor, Size};
+use embedded_graphics_framebuf::FrameBuf;
+use log::warn;
+
+use crate::components::{NpcComponent, Player};
+use crate::events::action::PlayerActionMessage;
+use crate::events::coin::CoinCollisionMessage;
+use crate::events::dynamite::DynamiteCollisionMessage;
+use crate::events::npc::NpcCollisionMessage;
+use crate::events::player::{PlayerAnalogInputMessage, PlayerInputMessage};
+use crate::events::walker::WalkerCollisionMessage;
+use crate::heapbuffer::VecBuffer;
+use crate::movement::MovementMode;
+use crate::render::dirty::{DirtyTracker, flush};
+use crate::render::pixel_format;
+use crate::render::tile_cache::TileCache;
+use crate::render::zoom::Zoom;
+use crate::render::{
+    camera_position, draw_touch_buttons, render_frame, render_frame_cached, sprite_position,
+};
+use crate::resources::{MazeResource, MazeSeed, PlayerPosition};
+use crate::seed_code::SeedCode;
+use crate::systems::collisions;
+use crate::systems::hud::HudState;
+use crate::systems::motion::StepInterpolation;
+use crate::systems::setup::{NoStdTransform, TextureAssets};
+use crate::systems::tilt_input::{DEFAULT_THRESHOLD, Tilt, TiltThreshold, dispatch_tilt_input};
+use crate::systems::{self, TickSet, process_player_input::process_player_input};
+use crate::touch::{TouchAction, TouchMapper};
+
+/// How long the board vibrates when an NPC catches the ghost.
+const CATCH_VIBRATION: Duration = Duration::from_millis(150);
+
+/// Hardware a game runs on. Only the display, the entropy source and the clock are
+/// required; boards without an accelerometer, touch screen or vibration motor keep
+/// the default methods.
+pub trait Board: 'static {
+    type Display: DrawTarget<Color: From<Rgb565> + Send + Sync, Error: Debug>;
+    /// Conversion from the `Rgb565` textures to the display's colour;
+    /// `pixel_format::Direct` for RGB panels.
+    type Palette: pixel_format::Palette<DisplayColor<Self>>;
+
+    /// Size of the display in pixels.
+    fn display_size(&self) -> Size;
+
+    /// The display the finished frames are flushed to.
+    fn display(&mut self) -> &mut Self::Display;
+
+    /// The palette the textures are converted with.
+    fn palette(&self) -> Self::Palette;
+
+    /// 32 bytes of entropy for the maze seed. `build_app` turns them into a
+    /// `SeedCode`; a seed that already is the expansion of a code keeps its code.
+    fn seed(&mut self) -> [u8; 32];
+
+    /// Monotonic time since start. Drives the game clock, so a board can also slow
+    /// down or fake time.
+    fn now(&self) -> Duration;
+
+    /// Latest accelerometer reading, rotated to the screen (see `Tilt`), or `None`
+    /// when there is no sensor or the read failed.
+    fn read_tilt(&mut self) -> Option<Tilt> {
+        None
+    }
+
+    /// Tilt (in g) below which `read_tilt` readings do not move the player.
+    fn tilt_threshold(&self) -> f32 {
+        DEFAULT_THRESHOLD
+    }
+
+    /// Whether the display has a touch panel; enables `read_touch` and the on-screen
+    /// buttons.
+    fn has_touch(&self) -> bool {
+        false
+    }
+
+    /// The point currently touched, in screen pixels, or `None` while the screen is
+    /// not touched.
+    fn read_touch(&mut self) -> Option<Point> {
+        None
+    }
+
+    /// Short haptic feedback.
+    fn vibrate(&mut self, _duration: Duration) {}
+
+    /// Called after every frame, e.g. to sleep or feed a watchdog.
+    fn idle(&mut self) {}
+}
+
+/// Game settings a board can choose.
+#[derive(Clone, Copy, Debug)]
+pub struct GameConfig {
+    pub zoom: Zoom,
+    pub movement_mode: MovementMode,
+    /// Duration of one game logic step.
+    pub game_step: Duration,
+    /// Render through the `TileCache`. Without it every frame is drawn with
+    /// `render_frame` straight from the BMPs, converted with `From<Rgb565>` instead
+    /// of the board's `Palette`, which saves the cache's memory.
+    pub tile_cache: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            zoom: Zoom::Normal,
+            movement_mode: MovementMode::Grid,
+            game_step: Duration::from_millis(300),
+            tile_cache: true,
+        }
+    }
+}
+
+/// The board, stored as a non-send resource because drivers are rarely `Sync`.
+pub struct BoardResource<B: Board>(pub B);
+
+/// Colour of a board's display.
+type DisplayColor<B> = <<B as Board>::Display as DrawTarget>::Color;
+
+/// The frame is rendered here, in the display's colour, before the changed regions
+/// are flushed to the display.
+#[derive(Resource)]
+pub struct BoardFrame<C: PixelColor> {
+    pub frame: FrameBuf<C, VecBuffer<C>>,
+}
+
+/// Whether `render_board` renders through the tile cache, from `GameConfig`.
+#[derive(Resource)]
+struct UseTileCache(bool);
+
+/// Board time at the previous frame.
+#[derive(Resource)]
+struct BoardClock {
+    last: Duration,
+}
+
+/// Build the app for a board: the shared game systems plus the systems that read
+/// the board's input and render to its display. Call `update` once per frame.
+pub fn build_app<B: Board>(mut board: B, config: GameConfig) -> App {
+    let size = board.display_size();
+    let tilt_threshold = board.tilt_threshold();
+    let seed = SeedCode::from_entropy(&board.seed()).to_seed();
+    let frame = FrameBuf::<DisplayColor<B>, _>::new(
+        VecBuffer::new(Rgb565::BLACK.into(), (size.width * size.height) as usize),
+        size.width as usize,
+        size.height as usize,
+    );
+
+    let mut app = App::new();
+    app.add_plugins((TaskPoolPlugin::default(), TimePlugin::default()));
+    add_game_systems(&mut app);
+    if board.has_touch() {
+        app.insert_resource(TouchMapper::new(size.width, size.height));
+    }
+    app.insert_resource(BoardClock { last: board.now() })
+        .insert_non_send_resource(BoardResource(board))
+        .insert_resource(BoardFrame { frame })
+        .insert_resource(HudState::default())
+        .insert_resource(MazeSeed(Some(seed)))
+        .insert_resource(config.zoom)
+        .insert_resource(config.movement_mode)
+        .insert_resource(UseTileCache(config.tile_cache))
+        .init_resource::<Tilt>()
+        .insert_resource(TiltThreshold(tilt_threshold))
+        // Game logic advances in fixed steps; rendering runs every frame and
+        // interpolates the sprites between two steps.
+        .insert_resource(Time::<Fixed>::from_duration(config.game_step))
+        .insert_resource(StepInterpolation {
+            duration: config.game_step.as_secs_f32(),
+        })
+        .add_systems(
+            FixedUpdate,
+            (
+                (read_board_tilt::<B>, dispatch_tilt_input)
+                    .chain()
+                    .in_set(TickSet::Input),
+                vibrate_on_catch::<B>,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                // Touch is polled every frame so that short taps are not missed.
+                read_board_touch::<B>,
+                (systems::motion::interpolate_motion, render_board::<B>).chain(),
+            ),
+        );
+    app
+}
+
+/// Register the game's messages, the maze setup at startup and the game logic that
+/// runs in `FixedUpdate`. Front-ends add their input and output systems on top,
+/// with the systems that write input messages in `TickSet::Input`; `build_app` does
+/// so for a `Board`.
+pub fn add_game_systems(app: &mut App) {
+    app.add_systems(Startup, systems::setup::setup)
+        .add_message::<PlayerInputMessage>()
+        .add_message::<PlayerAnalogInputMessage>()
+        .add_message::<PlayerActionMessage>()
+        .add_message::<CoinCollisionMessage>()
+        .add_message::<DynamiteCollisionMessage>()
+        .add_message::<WalkerCollisionMessage>()
+        .add_message::<NpcCollisionMessage>()
+        .configure_sets(
+            FixedUpdate,
+            (TickSet::Input, TickSet::Record, TickSet::Logic).chain(),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                process_player_input,
+                systems::continuous_movement::apply_continuous_movement,
+                systems::player_action::handle_player_action,
+                collisions::coin::detect_coin_collision,
+                collisions::coin::remove_coin_on_collision,
+                collisions::dynamite::handle_dynamite_collision,
+                collisions::walker::detect_walker_collision,
+                collisions::walker::handle_walker_collision,
+                collisions::npc::detect_npc_collision,
+                collisions::npc::handle_npc_collision,
+                systems::dynamite_logic::handle_dynamite_collision,
+                systems::npc_logic::update_npc_movement,
+                systems::game_logic::update_game,
+            )
+                .in_set(TickSet::Logic),
+        );
+}
+
+/// Advance the game by one frame, using the board's clock for the elapsed time.
+pub fn update<B: Board>(app: &mut App) {
+    let now = app.world().non_send_resource::<BoardResource<B>>().0.now();
+    let mut clock = app.world_mut().resource_mut::<BoardClock>();
+    let elapsed = now.saturating_sub(clock.last);
+    clock.last = now;
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(elapsed));
+    app.update();
+    app.world_mut()
+        .non_send_resource_mut::<BoardResource<B>>()
+        .0
+        .idle();
+}
+
+/// Build the app for a board and run it forever.
+pub fn run_game<B: Board>(board: B, config: GameConfig) -> ! {
+    let mut app = build_app(board, config);
+    loop {
+        update::<B>(&mut app);
+    }
+}
+
+fn read_board_tilt<B: Board>(mut board: NonSendMut<BoardResource<B>>, mut tilt: ResMut<Tilt>) {
+    if let Some(reading) = board.0.read_tilt() {
+        *tilt = reading;
+    }
+}
+
+/// Forwards touch points to the `TouchMapper`. Once the finger is lifted the gesture
+/// is dispatched either as a one-tile step or as an action.
+fn read_board_touch<B: Board>(
+    mut board: NonSendMut<BoardResource<B>>,
+    mapper: Option<ResMut<TouchMapper>>,
+    maze_res: Res<MazeResource>,
+    mut input_writer: MessageWriter<PlayerInputMessage>,
+    mut action_writer: MessageWriter<PlayerActionMessage>,
+) {
+    let Some(mut mapper) = mapper else {
+        return;
+    };
+    let gesture = match board.0.read_touch() {
+        Some(point) => {
+            mapper.press(point.x, point.y);
+            None
+        }
+        None => mapper.release(),
+    };
+
+    match gesture {
+        Some(TouchAction::Move { dx, dy }) if dx != 0 || dy != 0 => {
+            input_writer.write(PlayerInputMessage {
+                dx: dx as f32 * maze_res.maze.tile_width as f32,
+                dy: dy as f32 * maze_res.maze.tile_height as f32,
+            });
+        }
+        Some(TouchAction::Action(action)) => {
+            action_writer.write(PlayerActionMessage { action });
+        }
+        _ => {}
+    }
+}
+
+fn vibrate_on_catch<B: Board>(
+    mut board: NonSendMut<BoardResource<B>>,
+    mut events: MessageReader<NpcCollisionMessage>,
+) {
+    if events.read().count() > 0 {
+        board.0.vibrate(CATCH_VIBRATION);
+    }
+}
+
+/// Render the scene with the shared renderer, through the tile cache unless the
+/// board turned it off, then flush the regions that changed since the previous
+/// frame to the board's display.
+#[allow(clippy::too_many_arguments)]
+fn render_board<B: Board>(
+    mut board: NonSendMut<BoardResource<B>>,
+    mut frame_res: ResMut<BoardFrame<DisplayColor<B>>>,
+    maze_res: Res<MazeResource>,
+    texture_assets: Res<TextureAssets>,
+    player_pos: Res<PlayerPosition>,
+    player_query: Query<&NoStdTransform, With<Player>>,
+    npc_query: Query<&NoStdTransform, With<NpcComponent>>,
+    hud_state: Res<HudState>,
+    zoom: Option<Res<Zoom>>,
+    touch_mapper: Option<Res<TouchMapper>>,
+    use_tile_cache: Res<UseTileCache>,
+    mut dirty_tracker: Local<DirtyTracker>,
+    mut tile_cache: Local<Option<TileCache<DisplayColor<B>>>>,
+) {
+    let camera = camera_position(player_query.single().ok(), &player_pos);
+    let zoom = zoom.map(|zoom| *zoom).unwrap_or_default();
+    let frame = &mut frame_res.frame;
+    let npcs = npc_query.iter().map(sprite_position);
+    let summary = if use_tile_cache.0 {
+        // Decode the textures once per zoom; afterwards tiles are copied row by row.
+        if tile_cache.as_ref().is_none_or(|cache| cache.zoom() != zoom) {
+            *tile_cache = Some(TileCache::new(&texture_assets, zoom, &board.0.palette()));
+        }
+        let tile_cache = tile_cache.as_ref().unwrap();
+        render_frame_cached(frame, tile_cache, &maze_res.maze, camera, npcs, &hud_state)
+    } else {
+        render_frame(
+            frame,
+            &maze_res.maze,
+            &texture_assets,
+            camera,
+            zoom,
+            npcs,
+            &hud_state,
+        )
+    }
+    .unwrap();
+    if let Some(mapper) = touch_mapper {
+        draw_touch_buttons(frame, &mapper).unwrap();
+    }
+
+    let regions = dirty_tracker.update(&summary, &maze_res.maze, &hud_state);
+    if let Err(error) = flush(board.0.display(), frame, &regions) {
+        warn!("Display flush failed: {:?}", error);
+    }
+}
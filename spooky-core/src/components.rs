@@ -39,3 +39,24 @@ pub struct NpcComponent {
     pub x: i32,
     pub y: i32,
 }
+
+/// Presentation-only interpolation between two logical positions.
+/// Game logic stays tile based and retargets `to`; `interpolate_motion`
+/// then slides the transform from `from` to `to` over one step.
+#[derive(Component, Debug, Clone)]
+pub struct VisualMotion {
+    pub from: Vec2,
+    pub to: Vec2,
+    pub elapsed: f32,
+}
+
+impl VisualMotion {
+    /// A motion resting at the given position.
+    pub fn at(x: f32, y: f32) -> Self {
+        Self {
+            from: Vec2::new(x, y),
+            to: Vec2::new(x, y),
+            elapsed: 0.0,
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use bevy::prelude::Message;
+
+/// Discrete player actions that are not plain movement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerAction {
+    Teleport,
+    PlaceDynamite,
+}
+
+/// An event carrying a player action request (teleport, dynamite, ...).
+#[derive(Debug, Message)]
+pub struct PlayerActionMessage {
+    pub action: PlayerAction,
+}
@@ -1,3 +1,4 @@
+pub mod action;
 pub mod coin;
 pub mod dynamite;
 pub mod npc;
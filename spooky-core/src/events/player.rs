@@ -7,3 +7,12 @@ pub struct PlayerInputMessage {
     pub dx: f32,
     pub dy: f32,
 }
+
+/// Analog movement input for continuous movement mode.
+/// Each axis is in -1.0..=1.0 and scales the player's speed; a positive y moves up.
+/// Input sources send it every tick, also when the stick or tilt is at rest.
+#[derive(Debug, Message)]
+pub struct PlayerAnalogInputMessage {
+    pub x: f32,
+    pub y: f32,
+}
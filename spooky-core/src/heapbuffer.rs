@@ -1,4 +1,5 @@
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use embedded_graphics::prelude::*;
 use embedded_graphics_framebuf::backends::FrameBufferBackend;
 
@@ -37,3 +38,39 @@ impl<C: PixelColor, const N: usize> FrameBufferBackend for HeapBuffer<C, N> {
         N
     }
 }
+
+/// Heap-allocated framebuffer backend whose size is chosen at runtime, for code that
+/// is generic over the display size.
+pub struct VecBuffer<C: PixelColor>(Vec<C>);
+
+impl<C: PixelColor> VecBuffer<C> {
+    pub fn new(color: C, len: usize) -> Self {
+        Self(alloc::vec![color; len])
+    }
+}
+
+impl<C: PixelColor> core::ops::Deref for VecBuffer<C> {
+    type Target = [C];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<C: PixelColor> core::ops::DerefMut for VecBuffer<C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<C: PixelColor> FrameBufferBackend for VecBuffer<C> {
+    type Color = C;
+    fn set(&mut self, index: usize, color: Self::Color) {
+        self.0[index] = color;
+    }
+    fn get(&self, index: usize) -> Self::Color {
+        self.0[index]
+    }
+    fn nr_elements(&self) -> usize {
+        self.0.len()
+    }
+}
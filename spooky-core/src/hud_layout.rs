@@ -0,0 +1,176 @@
+//! Description of the HUD, shared by every frontend.
+//!
+//! The HUD is a list of widgets, each with a style and an area on screen. Presets
+//! cover the 320x240 panels, the 128x128 Atom panels and the desktop window; the
+//! embedded renderer picks one by display size, the desktop builds its UI nodes from
+//! `HudLayout::DESKTOP`.
+
+use core::fmt;
+use embedded_graphics::prelude::{Point, Size};
+use embedded_graphics::primitives::Rectangle;
+
+use crate::systems::hud::HudState;
+use crate::systems::player_action::TELEPORT_READY;
+
+/// Full scale of the walker timer bar.
+pub const WALKER_TIMER_MAX: u32 = 100;
+
+/// A value shown on the HUD.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HudWidget {
+    Coins,
+    Teleport,
+    WalkerTimer,
+    Dynamite,
+    Level,
+    /// Code of the maze seed, so that players can share the maze.
+    Seed,
+}
+
+impl HudWidget {
+    pub fn label(self) -> &'static str {
+        match self {
+            HudWidget::Coins => "Coins",
+            HudWidget::Teleport => "Teleport",
+            HudWidget::WalkerTimer => "Walker",
+            HudWidget::Dynamite => "Dynamite",
+            HudWidget::Level => "Level",
+            HudWidget::Seed => "Seed",
+        }
+    }
+
+    /// Label used when the full one does not fit.
+    pub fn short_label(self) -> &'static str {
+        match self {
+            HudWidget::Coins => "C",
+            HudWidget::Teleport => "T",
+            HudWidget::WalkerTimer => "W",
+            HudWidget::Dynamite => "D",
+            HudWidget::Level => "L",
+            HudWidget::Seed => "S",
+        }
+    }
+
+    /// The number shown by the widget; the seed is no number and has 0.
+    pub fn value(self, hud_state: &HudState) -> u32 {
+        match self {
+            HudWidget::Coins => hud_state.coins_left,
+            HudWidget::Teleport => hud_state.teleport_countdown,
+            HudWidget::WalkerTimer => hud_state.walker_timer,
+            HudWidget::Dynamite => hud_state.dynamites,
+            HudWidget::Level => hud_state.level,
+            HudWidget::Seed => 0,
+        }
+    }
+
+    /// Write the value as the text styles show it: the number, or the seed code.
+    pub fn write_value<W: fmt::Write>(self, out: &mut W, hud_state: &HudState) -> fmt::Result {
+        match (self, hud_state.seed) {
+            (HudWidget::Seed, Some(seed)) => write!(out, "{}", seed),
+            (HudWidget::Seed, None) => out.write_str("-"),
+            _ => write!(out, "{}", self.value(hud_state)),
+        }
+    }
+
+    /// Value at which a bar is full, for widgets that can be shown as a bar.
+    pub fn maximum(self) -> Option<u32> {
+        match self {
+            HudWidget::Teleport => Some(TELEPORT_READY),
+            HudWidget::WalkerTimer => Some(WALKER_TIMER_MAX),
+            _ => None,
+        }
+    }
+}
+
+/// How a widget is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WidgetStyle {
+    /// "Label: value", or the short label when the area is too narrow.
+    Text,
+    /// The widget's icon followed by the value.
+    IconCount,
+    /// One icon per unit, as many as fit into the area.
+    Icons,
+    /// A horizontal bar filled up to `value / maximum`.
+    Bar,
+}
+
+/// A widget placed on screen. `area` is in screen pixels, origin top-left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WidgetLayout {
+    pub widget: HudWidget,
+    pub style: WidgetStyle,
+    pub area: Rectangle,
+}
+
+impl WidgetLayout {
+    const fn new(
+        widget: HudWidget,
+        style: WidgetStyle,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self {
+            widget,
+            style,
+            area: Rectangle::new(Point::new(x, y), Size::new(width, height)),
+        }
+    }
+}
+
+/// The widgets of one HUD, drawn in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HudLayout {
+    pub widgets: &'static [WidgetLayout],
+}
+
+impl HudLayout {
+    /// 320x240 panels (ESP32-S3-BOX-3, M5Stack Core2 and CoreS3): full-size icons
+    /// along the top edge, bars for the timers below them.
+    pub const LARGE: HudLayout = HudLayout {
+        widgets: &[
+            WidgetLayout::new(HudWidget::Coins, WidgetStyle::IconCount, 4, 4, 60, 16),
+            WidgetLayout::new(HudWidget::Dynamite, WidgetStyle::Icons, 68, 4, 80, 16),
+            WidgetLayout::new(HudWidget::Level, WidgetStyle::Text, 256, 4, 60, 16),
+            WidgetLayout::new(HudWidget::Seed, WidgetStyle::Text, 152, 4, 100, 16),
+            WidgetLayout::new(HudWidget::Teleport, WidgetStyle::Bar, 4, 24, 80, 5),
+            WidgetLayout::new(HudWidget::WalkerTimer, WidgetStyle::Bar, 4, 32, 80, 5),
+        ],
+    };
+
+    /// 128x128 panels (M5Stack Atom S3 and S3R): half-size icons and thin bars, no
+    /// labels.
+    pub const SMALL: HudLayout = HudLayout {
+        widgets: &[
+            WidgetLayout::new(HudWidget::Coins, WidgetStyle::IconCount, 2, 2, 36, 10),
+            WidgetLayout::new(HudWidget::Dynamite, WidgetStyle::Icons, 40, 2, 40, 10),
+            WidgetLayout::new(HudWidget::Level, WidgetStyle::Text, 104, 2, 24, 10),
+            WidgetLayout::new(HudWidget::Teleport, WidgetStyle::Bar, 2, 14, 36, 3),
+            WidgetLayout::new(HudWidget::WalkerTimer, WidgetStyle::Bar, 2, 19, 36, 3),
+            WidgetLayout::new(HudWidget::Seed, WidgetStyle::Text, 40, 13, 88, 10),
+        ],
+    };
+
+    /// Desktop and web window: one text entry per widget in a row.
+    pub const DESKTOP: HudLayout = HudLayout {
+        widgets: &[
+            WidgetLayout::new(HudWidget::Coins, WidgetStyle::Text, 5, 5, 120, 24),
+            WidgetLayout::new(HudWidget::Teleport, WidgetStyle::Text, 130, 5, 140, 24),
+            WidgetLayout::new(HudWidget::WalkerTimer, WidgetStyle::Text, 275, 5, 120, 24),
+            WidgetLayout::new(HudWidget::Dynamite, WidgetStyle::Text, 400, 5, 140, 24),
+            WidgetLayout::new(HudWidget::Level, WidgetStyle::Text, 545, 5, 100, 24),
+            WidgetLayout::new(HudWidget::Seed, WidgetStyle::Text, 650, 5, 160, 24),
+        ],
+    };
+
+    /// The preset for a display of the given size.
+    pub fn for_display(size: Size) -> HudLayout {
+        if size.width >= 240 && size.height >= 160 {
+            HudLayout::LARGE
+        } else {
+            HudLayout::SMALL
+        }
+    }
+}
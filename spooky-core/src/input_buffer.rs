@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use heapless::Deque;
+
+/// Number of taps that can wait for the next fixed tick before the oldest is dropped.
+const PENDING_CAPACITY: usize = 8;
+
+/// Key-repeat behaviour of the buffered movement input.
+#[derive(Resource, Clone, Debug)]
+pub struct InputRepeatConfig {
+    /// Seconds a direction has to be held before it starts repeating.
+    pub initial_delay: f32,
+    /// Repeated moves per second while a direction is held.
+    pub repeat_rate: f32,
+    /// Whether a held direction may combine both axes into one diagonal step.
+    pub allow_diagonals: bool,
+}
+
+impl Default for InputRepeatConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay: 0.25,
+            repeat_rate: 10.0,
+            allow_diagonals: false,
+        }
+    }
+}
+
+/// Collects movement input at frame rate and hands it out one move per fixed tick.
+///
+/// Taps are queued by `press` and replayed in order, so a key that goes down and up
+/// between two ticks still moves the player. Held directions are reported through
+/// `set_held` and repeat after `initial_delay` at `repeat_rate`. Directions are unit
+/// steps (-1, 0 or 1 per axis, positive dy is up); times are seconds on a shared clock.
+#[derive(Resource, Debug)]
+pub struct InputBuffer {
+    pending: Deque<(i32, i32), PENDING_CAPACITY>,
+    held: (i32, i32),
+    next_repeat: f32,
+    last_axis_horizontal: bool,
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self {
+            pending: Deque::new(),
+            held: (0, 0),
+            next_repeat: 0.0,
+            last_axis_horizontal: true,
+        }
+    }
+}
+
+impl InputBuffer {
+    /// Record a direction key going down.
+    pub fn press(&mut self, dx: i32, dy: i32) {
+        if dx == 0 && dy == 0 {
+            return;
+        }
+        self.last_axis_horizontal = dx != 0;
+        if self.pending.is_full() {
+            self.pending.pop_front();
+        }
+        let _ = self.pending.push_back((dx.signum(), dy.signum()));
+    }
+
+    /// Report the currently held direction. Call once per frame, also when nothing is held.
+    pub fn set_held(&mut self, dx: i32, dy: i32, now: f32, config: &InputRepeatConfig) {
+        let mut held = (dx.signum(), dy.signum());
+        if !config.allow_diagonals && held.0 != 0 && held.1 != 0 {
+            // Keep only the axis that was pressed most recently.
+            if self.last_axis_horizontal {
+                held.1 = 0;
+            } else {
+                held.0 = 0;
+            }
+        }
+        if held != self.held {
+            self.held = held;
+            self.next_repeat = now + config.initial_delay;
+        }
+    }
+
+    /// The move to apply on this fixed tick: queued taps first, then key repeat.
+    pub fn next_move(&mut self, now: f32, config: &InputRepeatConfig) -> Option<(i32, i32)> {
+        if let Some(pending) = self.pending.pop_front() {
+            return Some(pending);
+        }
+        if self.held == (0, 0) || now < self.next_repeat {
+            return None;
+        }
+        let interval = if config.repeat_rate > 0.0 {
+            1.0 / config.repeat_rate
+        } else {
+            f32::INFINITY
+        };
+        // Do not accumulate a burst of repeats after a long frame.
+        self.next_repeat = (self.next_repeat + interval).max(now);
+        Some(self.held)
+    }
+
+    /// Forget queued taps and the held direction.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.held = (0, 0);
+    }
+}
@@ -0,0 +1,368 @@
+use bevy::prelude::*;
+use bevy_input::ButtonInput;
+use bevy_input::keyboard::KeyCode;
+use core::fmt;
+
+/// Every action that can be bound to a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Teleport,
+    PlaceDynamite,
+    Pause,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 7] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Teleport,
+        InputAction::PlaceDynamite,
+        InputAction::Pause,
+    ];
+
+    /// Name used in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "move_up",
+            InputAction::MoveDown => "move_down",
+            InputAction::MoveLeft => "move_left",
+            InputAction::MoveRight => "move_right",
+            InputAction::Teleport => "teleport",
+            InputAction::PlaceDynamite => "place_dynamite",
+            InputAction::Pause => "pause",
+        }
+    }
+
+    /// Human readable label for menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Move up",
+            InputAction::MoveDown => "Move down",
+            InputAction::MoveLeft => "Move left",
+            InputAction::MoveRight => "Move right",
+            InputAction::Teleport => "Teleport",
+            InputAction::PlaceDynamite => "Place dynamite",
+            InputAction::Pause => "Pause",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// Number of keys that can be bound to a single action (primary and secondary).
+pub const SLOTS_PER_ACTION: usize = 2;
+
+/// Maps physical keys to actions. Each action has a primary and a secondary slot.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    slots: [[Option<KeyCode>; SLOTS_PER_ACTION]; InputAction::ALL.len()],
+}
+
+/// Returned when a key is already used by another action or slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BindingConflict {
+    pub key: KeyCode,
+    pub action: InputAction,
+    pub slot: usize,
+}
+
+impl fmt::Display for BindingConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is already bound to {}",
+            key_name(self.key).unwrap_or("key"),
+            self.action.label()
+        )
+    }
+}
+
+/// Errors reported while parsing a key map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyBindingsError {
+    /// A line did not have the `action = Key, Key` shape.
+    Syntax {
+        line: usize,
+    },
+    UnknownAction {
+        line: usize,
+        name: String,
+    },
+    UnknownKey {
+        line: usize,
+        name: String,
+    },
+    TooManyKeys {
+        line: usize,
+    },
+    Conflict(BindingConflict),
+}
+
+impl fmt::Display for KeyBindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyBindingsError::Syntax { line } => {
+                write!(f, "line {}: expected `action = Key`", line)
+            }
+            KeyBindingsError::UnknownAction { line, name } => {
+                write!(f, "line {}: unknown action `{}`", line, name)
+            }
+            KeyBindingsError::UnknownKey { line, name } => {
+                write!(f, "line {}: unknown key `{}`", line, name)
+            }
+            KeyBindingsError::TooManyKeys { line } => write!(
+                f,
+                "line {}: at most {} keys per action",
+                line, SLOTS_PER_ACTION
+            ),
+            KeyBindingsError::Conflict(conflict) => conflict.fmt(f),
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    /// Arrows and WASD for movement, Space to teleport, Enter for dynamite, Escape to pause.
+    fn default() -> Self {
+        let mut bindings = Self::empty();
+        let defaults = [
+            (InputAction::MoveUp, [KeyCode::ArrowUp, KeyCode::KeyW]),
+            (InputAction::MoveDown, [KeyCode::ArrowDown, KeyCode::KeyS]),
+            (InputAction::MoveLeft, [KeyCode::ArrowLeft, KeyCode::KeyA]),
+            (InputAction::MoveRight, [KeyCode::ArrowRight, KeyCode::KeyD]),
+        ];
+        for (action, keys) in defaults {
+            for (slot, key) in keys.into_iter().enumerate() {
+                bindings.slots[action as usize][slot] = Some(key);
+            }
+        }
+        bindings.slots[InputAction::Teleport as usize][0] = Some(KeyCode::Space);
+        bindings.slots[InputAction::PlaceDynamite as usize][0] = Some(KeyCode::Enter);
+        bindings.slots[InputAction::Pause as usize][0] = Some(KeyCode::Escape);
+        bindings
+    }
+}
+
+impl KeyBindings {
+    /// A key map without any bindings.
+    pub fn empty() -> Self {
+        Self {
+            slots: [[None; SLOTS_PER_ACTION]; InputAction::ALL.len()],
+        }
+    }
+
+    /// The key bound to the given slot of an action.
+    pub fn key(&self, action: InputAction, slot: usize) -> Option<KeyCode> {
+        self.slots[action as usize].get(slot).copied().flatten()
+    }
+
+    /// All keys bound to an action.
+    pub fn keys(&self, action: InputAction) -> impl Iterator<Item = KeyCode> + '_ {
+        self.slots[action as usize].iter().flatten().copied()
+    }
+
+    /// The action (and slot) a key is bound to, if any.
+    pub fn binding_of(&self, key: KeyCode) -> Option<(InputAction, usize)> {
+        InputAction::ALL.into_iter().find_map(|action| {
+            self.slots[action as usize]
+                .iter()
+                .position(|bound| *bound == Some(key))
+                .map(|slot| (action, slot))
+        })
+    }
+
+    /// True while any key bound to the action is held.
+    pub fn pressed(&self, input: &ButtonInput<KeyCode>, action: InputAction) -> bool {
+        self.keys(action).any(|key| input.pressed(key))
+    }
+
+    /// True in the frame any key bound to the action went down.
+    pub fn just_pressed(&self, input: &ButtonInput<KeyCode>, action: InputAction) -> bool {
+        self.keys(action).any(|key| input.just_pressed(key))
+    }
+
+    /// Bind `key` to a slot of `action`. Fails without changing anything when the key
+    /// is already used elsewhere; rebinding a key to the slot it already occupies is a no-op.
+    pub fn rebind(
+        &mut self,
+        action: InputAction,
+        slot: usize,
+        key: KeyCode,
+    ) -> Result<(), BindingConflict> {
+        if let Some((bound_action, bound_slot)) = self.binding_of(key) {
+            if (bound_action, bound_slot) != (action, slot) {
+                return Err(BindingConflict {
+                    key,
+                    action: bound_action,
+                    slot: bound_slot,
+                });
+            }
+        }
+        self.slots[action as usize][slot] = Some(key);
+        Ok(())
+    }
+
+    /// Remove the key from a slot.
+    pub fn clear(&mut self, action: InputAction, slot: usize) {
+        self.slots[action as usize][slot] = None;
+    }
+
+    /// Serialize into the `action = Key, Key` text format used by the config file.
+    pub fn to_config_string(&self) -> String {
+        let mut out = String::from("# Spooky Maze key bindings: action = Key[, Key]\n");
+        for action in InputAction::ALL {
+            let keys: Vec<&str> = self.keys(action).filter_map(key_name).collect();
+            out.push_str(action.name());
+            out.push_str(" = ");
+            out.push_str(&keys.join(", "));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse the text format produced by `to_config_string`. Actions missing from the
+    /// text keep no binding, so a partial file can deliberately unbind an action.
+    pub fn from_config_str(text: &str) -> Result<Self, KeyBindingsError> {
+        let mut bindings = Self::empty();
+        for (index, raw_line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, keys) = line
+                .split_once('=')
+                .ok_or(KeyBindingsError::Syntax { line: line_number })?;
+            let name = name.trim();
+            let action =
+                InputAction::from_name(name).ok_or_else(|| KeyBindingsError::UnknownAction {
+                    line: line_number,
+                    name: name.to_string(),
+                })?;
+            let keys = keys.split(',').map(str::trim).filter(|key| !key.is_empty());
+            for (slot, key_str) in keys.enumerate() {
+                if slot >= SLOTS_PER_ACTION {
+                    return Err(KeyBindingsError::TooManyKeys { line: line_number });
+                }
+                let key = key_from_name(key_str).ok_or_else(|| KeyBindingsError::UnknownKey {
+                    line: line_number,
+                    name: key_str.to_string(),
+                })?;
+                bindings
+                    .rebind(action, slot, key)
+                    .map_err(KeyBindingsError::Conflict)?;
+            }
+        }
+        Ok(bindings)
+    }
+}
+
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        /// Keys that can appear in a key map, named after their `KeyCode` variant.
+        const KEY_NAMES: &[(KeyCode, &str)] = &[$((KeyCode::$key, stringify!($key))),*];
+    };
+}
+
+key_names!(
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadEnter,
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    ShiftLeft,
+    ShiftRight,
+    ControlLeft,
+    ControlRight,
+    AltLeft,
+    AltRight,
+    Comma,
+    Period,
+    Slash,
+    Semicolon,
+    Quote,
+    BracketLeft,
+    BracketRight,
+    Minus,
+    Equal,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+);
+
+/// Config-file name of a key, `None` for keys that cannot be bound.
+pub fn key_name(key: KeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(code, _)| *code == key)
+        .map(|(_, name)| *name)
+}
+
+/// Inverse of `key_name`.
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, key_name)| *key_name == name)
+        .map(|(code, _)| *code)
+}
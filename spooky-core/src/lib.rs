@@ -1,13 +1,28 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
 // Expose modules for the core logic.
+#[cfg(not(feature = "std"))]
+pub mod board;
 pub mod components;
 pub mod events;
+pub mod heapbuffer;
+pub mod hud_layout;
+pub mod input_buffer;
+#[cfg(feature = "std")]
+pub mod key_bindings;
 pub mod maze;
+pub mod movement;
+#[cfg(not(feature = "std"))]
+pub mod render;
+pub mod replay;
 pub mod resources;
+pub mod rng;
+pub mod seed_code;
 pub mod systems;
+pub mod touch;
 mod transform;
 
 pub mod sprite_buf;
-#[cfg(feature = "static_maze")]
 mod static_maze_data;
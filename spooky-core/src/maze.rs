@@ -5,8 +5,66 @@
 #[cfg(feature = "dynamic_maze")]
 use maze_generator::{prelude::*, recursive_backtracking::RbGenerator};
 
-use rand::prelude::*;
-use rand_chacha::ChaChaRng;
+#[cfg(feature = "dynamic_maze")]
+use alloc::vec;
+#[cfg(feature = "dynamic_maze")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::rng::StreamRng;
+
+/// How `generate_maze_with` carves the passages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MazeAlgorithm {
+    /// Recursive backtracking: long winding corridors with few branches.
+    #[default]
+    Backtracking,
+    /// Randomized Prim's algorithm: many short dead ends.
+    Prim,
+}
+
+impl MazeAlgorithm {
+    pub const ALL: [MazeAlgorithm; 2] = [MazeAlgorithm::Backtracking, MazeAlgorithm::Prim];
+
+    /// Name used in URLs and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            MazeAlgorithm::Backtracking => "backtracking",
+            MazeAlgorithm::Prim => "prim",
+        }
+    }
+}
+
+impl fmt::Display for MazeAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A name that is not a `MazeAlgorithm`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnknownAlgorithm;
+
+impl fmt::Display for UnknownAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown maze algorithm, expected `backtracking` or `prim`"
+        )
+    }
+}
+
+impl FromStr for MazeAlgorithm {
+    type Err = UnknownAlgorithm;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        MazeAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+            .ok_or(UnknownAlgorithm)
+    }
+}
 
 #[derive(Copy, Clone)]
 pub struct Coin {
@@ -38,20 +96,30 @@ pub struct Maze {
     pub data: [u8; 64 * 64],
     pub coins: [Coin; 100],
     pub coin_counter: u32,
+    /// Coins placed by `place_coins`; relocating coins never adds more.
+    pub coin_total: u32,
     pub npcs: [Npc; 5],
+    /// NPCs placed by `place_npcs`; the other entries stay unused at (-1, -1).
+    pub npc_count: usize,
     pub walkers: [Coin; 5],
     pub dynamites: [Coin; 1],
     pub offset: u32,
     pub tile_width: u32,
     pub tile_height: u32,
-    rng: ChaChaRng,
+    /// Width and height in tiles of the part of `data` the maze was generated in,
+    /// from the top left corner; items are only placed there.
+    pub area_width: u32,
+    pub area_height: u32,
 }
 
 impl Maze {
     pub const MARGIN: i32 = 0;
+    pub const MAX_COINS: u32 = 100;
+    pub const MAX_NPCS: usize = 5;
 
-    /// Create a new maze with the given dimensions and an optional seed.
-    pub fn new(width: u32, height: u32, seed: Option<[u8; 32]>) -> Self {
+    /// Create a new maze with the given dimensions. The random numbers come from the
+    /// `GameRng` streams passed to the methods that place things.
+    pub fn new(width: u32, height: u32) -> Self {
         Self {
             width,
             height,
@@ -64,8 +132,11 @@ impl Maze {
             offset: width + 1,
             tile_width: 16,
             tile_height: 16,
+            area_width: width,
+            area_height: height,
             coins: [Coin { x: -1, y: -1 }; 100],
             coin_counter: 100,
+            coin_total: Self::MAX_COINS,
             npcs: [Npc {
                 x: -1,
                 y: -1,
@@ -73,20 +144,12 @@ impl Maze {
                 vector_y: 0,
                 steps_remaining: 0,
             }; 5],
+            npc_count: Self::MAX_NPCS,
             walkers: [Coin { x: -1, y: -1 }; 5],
             dynamites: [Coin { x: -1, y: -1 }; 1],
-            rng: match seed {
-                None => ChaChaRng::from_seed([42; 32]),
-                Some(s) => ChaChaRng::from_seed(s),
-            },
         }
     }
 
-    /// Return a random number in the range 0..255.
-    pub fn get_rand(&mut self) -> i32 {
-        self.rng.gen_range(0..255)
-    }
-
     /// Check if a given pixel coordinate is outside the maze boundaries.
     pub fn check_boundary_collision(&self, x: i32, y: i32) -> bool {
         x < 0
@@ -122,13 +185,13 @@ impl Maze {
 
     /// Return a random valid coordinate (in pixel space) where the tile is walkable.
     /// Instead of building an entire list of valid coordinates, we repeatedly generate
-    /// random tile indices (which are in the range 0..width and 0..height) and check if
+    /// random tile indices (in the range 0..area_width and 0..area_height) and check if
     /// that tile is walkable. We try at most 10 times before returning a default coordinate.
-    pub fn get_random_coordinates(&mut self) -> (i32, i32) {
+    pub fn get_random_coordinates(&self, rng: &mut StreamRng) -> (i32, i32) {
         const MAX_ATTEMPTS: usize = 10;
         for _ in 0..MAX_ATTEMPTS {
-            let tx = self.rng.gen_range(0..self.width as i32);
-            let ty = self.rng.gen_range(0..self.height as i32);
+            let tx = rng.below(self.area_width) as i32;
+            let ty = rng.below(self.area_height) as i32;
             // Compute pixel coordinate for the tile (using the top-left corner).
             let x = tx * self.tile_width as i32;
             let y = ty * self.tile_height as i32;
@@ -145,20 +208,27 @@ impl Maze {
 
     // The following methods remain unchanged (coin generation, NPC movement, etc.)
 
-    pub fn generate_coins(&mut self) {
-        for index in 0..100 {
-            let (new_x, new_y) = self.get_random_coordinates();
+    pub fn generate_coins(&mut self, rng: &mut StreamRng) {
+        self.place_coins(Self::MAX_COINS, rng);
+    }
+
+    /// Place `count` coins, at most `MAX_COINS`, on random free tiles.
+    pub fn place_coins(&mut self, count: u32, rng: &mut StreamRng) {
+        let count = count.min(Self::MAX_COINS);
+        for index in 0..count as usize {
+            let (new_x, new_y) = self.get_random_coordinates(rng);
             self.coins[index].x = new_x;
             self.coins[index].y = new_y;
         }
-        self.coin_counter = 100;
+        self.coin_total = count;
+        self.coin_counter = count;
     }
 
-    pub fn relocate_coins(&mut self, amount: u32) {
+    pub fn relocate_coins(&mut self, amount: u32, rng: &mut StreamRng) {
         let mut relocate_counter = 0;
-        for index in 0..100 {
+        for index in 0..self.coin_total as usize {
             if self.coins[index].x == -1 && self.coins[index].y == -1 {
-                let (new_x, new_y) = self.get_random_coordinates();
+                let (new_x, new_y) = self.get_random_coordinates(rng);
                 self.coins[index].x = new_x;
                 self.coins[index].y = new_y;
                 relocate_counter += 1;
@@ -170,35 +240,39 @@ impl Maze {
         }
     }
 
-    pub fn generate_walkers(&mut self) {
+    pub fn generate_walkers(&mut self, rng: &mut StreamRng) {
         for index in 0..5 {
-            let (new_x, new_y) = self.get_random_coordinates();
+            let (new_x, new_y) = self.get_random_coordinates(rng);
             self.walkers[index].x = new_x;
             self.walkers[index].y = new_y;
         }
     }
 
-    pub fn generate_dynamites(&mut self) {
+    pub fn generate_dynamites(&mut self, rng: &mut StreamRng) {
         for index in 0..1 {
-            let (new_x, new_y) = self.get_random_coordinates();
+            let (new_x, new_y) = self.get_random_coordinates(rng);
             self.dynamites[index].x = new_x;
             self.dynamites[index].y = new_y;
         }
     }
 
-    pub fn generate_npcs(&mut self) {
-        for index in 0..5 {
-            let (new_x, new_y) = self.get_random_coordinates();
+    pub fn generate_npcs(&mut self, rng: &mut StreamRng) {
+        self.place_npcs(Self::MAX_NPCS, rng);
+    }
+
+    /// Place `count` NPCs, at most `MAX_NPCS`, with random directions.
+    pub fn place_npcs(&mut self, count: usize, rng: &mut StreamRng) {
+        self.npc_count = count.min(Self::MAX_NPCS);
+        for index in 0..self.npc_count {
+            let (new_x, new_y) = self.get_random_coordinates(rng);
             self.npcs[index].x = new_x;
             self.npcs[index].y = new_y;
             // Choose a random direction from the four cardinal directions.
-            let directions = [(1, 0), (-1, 0), (0, 1), (0, -1)];
-            let idx = self.get_rand() as usize % 4;
-            let (vx, vy) = directions[idx];
+            let (vx, vy) = rng.choose(&[(1, 0), (-1, 0), (0, 1), (0, -1)]);
             self.npcs[index].vector_x = vx;
             self.npcs[index].vector_y = vy;
             // Random steps between 1 and 4.
-            self.npcs[index].steps_remaining = (self.get_rand() % 4) + 1;
+            self.npcs[index].steps_remaining = rng.range(1..5);
         }
     }
 
@@ -242,20 +316,20 @@ impl Maze {
         }
     }
 
-    pub fn relocate_walker(&mut self, walker: Coin) {
+    pub fn relocate_walker(&mut self, walker: Coin, rng: &mut StreamRng) {
         for index in 0..5 {
             if self.walkers[index].x == walker.x && self.walkers[index].y == walker.y {
-                let (new_x, new_y) = self.get_random_coordinates();
+                let (new_x, new_y) = self.get_random_coordinates(rng);
                 self.walkers[index].x = new_x;
                 self.walkers[index].y = new_y;
             }
         }
     }
 
-    pub fn relocate_dynamite(&mut self, dynamite: Coin) {
+    pub fn relocate_dynamite(&mut self, dynamite: Coin, rng: &mut StreamRng) {
         for index in 0..1 {
             if self.dynamites[index].x == dynamite.x && self.dynamites[index].y == dynamite.y {
-                let (new_x, new_y) = self.get_random_coordinates();
+                let (new_x, new_y) = self.get_random_coordinates(rng);
                 self.dynamites[index].x = new_x;
                 self.dynamites[index].y = new_y;
             }
@@ -284,23 +358,23 @@ impl Maze {
         self.set_tile_at(x + tw, y + tw, 2);
     }
 
-    fn get_random_vector(&mut self) -> (i32, i32) {
-        let mut x = self.get_rand() % 3 - 1;
-        let y = self.get_rand() % 3 - 1;
+    fn get_random_vector(rng: &mut StreamRng) -> (i32, i32) {
+        let mut x = rng.range(-1..2);
+        let y = rng.range(-1..2);
         if x == 0 && y == 0 {
             x = 1;
         }
         (x, y)
     }
 
-    pub fn move_npcs(&mut self) {
-        for index in 0..5 {
+    pub fn move_npcs(&mut self, rng: &mut StreamRng) {
+        for index in 0..self.npc_count {
             let mut x = self.npcs[index].x;
             let mut y = self.npcs[index].y;
             x += self.npcs[index].vector_x * self.tile_width as i32;
             y += self.npcs[index].vector_y * self.tile_height as i32;
             if self.check_wall_collision(x, y) {
-                let (vx, vy) = self.get_random_vector();
+                let (vx, vy) = Self::get_random_vector(rng);
                 self.npcs[index].vector_x = vx;
                 self.npcs[index].vector_y = vy;
             } else {
@@ -310,32 +384,62 @@ impl Maze {
         }
     }
 
+    /// Replace the tiles with the fixed maze of the `static_maze` feature, which
+    /// dynamic builds can also play.
+    pub fn load_static_layout(&mut self) {
+        self.data = crate::static_maze_data::STATIC_MAZE_DATA;
+        self.area_width = self.width;
+        self.area_height = self.height;
+    }
+
+    /// Carve the maze with recursive backtracking on a graph of the given size.
+    pub fn generate_maze(&mut self, graph_width: usize, graph_height: usize, rng: &mut StreamRng) {
+        self.generate_maze_with(graph_width, graph_height, MazeAlgorithm::Backtracking, rng);
+    }
+
     #[cfg(feature = "static_maze")]
-    pub fn generate_maze(&mut self, _graph_width: usize, _graph_height: usize) {
+    pub fn generate_maze_with(
+        &mut self,
+        _graph_width: usize,
+        _graph_height: usize,
+        _algorithm: MazeAlgorithm,
+        _rng: &mut StreamRng,
+    ) {
         // No dynamic generation in static mode.
     }
 
+    /// Carve the maze on a graph of the given size, at most 32x32 for the 64x64 tiles
+    /// of `data`. Each algorithm is seeded with 32 bytes from `rng`, the `Layout`
+    /// stream of the game.
     #[cfg(feature = "dynamic_maze")]
-    pub fn generate_maze(&mut self, graph_width: usize, graph_height: usize) {
-        let seed: [u8; 32] = self.rng.r#gen();
-        let mut generator = RbGenerator::new(Some(seed));
-        let maze_graph = generator
-            .generate(graph_width as i32, graph_height as i32)
-            .unwrap();
+    pub fn generate_maze_with(
+        &mut self,
+        graph_width: usize,
+        graph_height: usize,
+        algorithm: MazeAlgorithm,
+        rng: &mut StreamRng,
+    ) {
+        let seed = rng.seed();
+        let passages = match algorithm {
+            MazeAlgorithm::Backtracking => backtracking_passages(seed, graph_width, graph_height),
+            MazeAlgorithm::Prim => prim_passages(seed, graph_width, graph_height),
+        };
         for y in 1..graph_height {
             for x in 1..graph_width {
-                let field = maze_graph.get_field(&(x as i32, y as i32).into()).unwrap();
+                let (west, south) = passages[y * graph_width + x];
                 let tile_index =
                     (x - 1) * 2 + (y - 1) * 2 * (self.width as usize) + (self.offset as usize);
                 self.data[tile_index] = 0;
-                if field.has_passage(&Direction::West) {
+                if west {
                     self.data[tile_index + 1] = 0;
                 }
-                if field.has_passage(&Direction::South) {
+                if south {
                     self.data[tile_index + (self.width as usize)] = 0;
                 }
             }
         }
+        self.area_width = (graph_width as u32 * 2).min(self.width);
+        self.area_height = (graph_height as u32 * 2).min(self.height);
     }
 
     pub fn playable_bounds(&self) -> (i32, i32, i32, i32) {
@@ -347,3 +451,84 @@ impl Maze {
         (left, bottom, right, top)
     }
 }
+
+/// The West and South passages of every field of a `maze_generator` graph, indexed
+/// by `y * graph_width + x`.
+#[cfg(feature = "dynamic_maze")]
+fn backtracking_passages(
+    seed: [u8; 32],
+    graph_width: usize,
+    graph_height: usize,
+) -> Vec<(bool, bool)> {
+    let mut generator = RbGenerator::new(Some(seed));
+    let maze_graph = generator
+        .generate(graph_width as i32, graph_height as i32)
+        .unwrap();
+    let mut passages = vec![(false, false); graph_width * graph_height];
+    for y in 1..graph_height {
+        for x in 1..graph_width {
+            let field = maze_graph.get_field(&(x as i32, y as i32).into()).unwrap();
+            passages[y * graph_width + x] = (
+                field.has_passage(&Direction::West),
+                field.has_passage(&Direction::South),
+            );
+        }
+    }
+    passages
+}
+
+/// Randomized Prim's algorithm on the fields `1..graph_width` x `1..graph_height`,
+/// with the same layout as `backtracking_passages`: the West passage of a field leads
+/// to `x + 1` and the South passage to `y + 1`, as they are carved into the tiles.
+#[cfg(feature = "dynamic_maze")]
+fn prim_passages(seed: [u8; 32], graph_width: usize, graph_height: usize) -> Vec<(bool, bool)> {
+    let mut rng = StreamRng::new(seed);
+    let mut passages = vec![(false, false); graph_width * graph_height];
+    if graph_width < 2 || graph_height < 2 {
+        return passages;
+    }
+    let mut in_maze = vec![false; graph_width * graph_height];
+    let mut queued = vec![false; graph_width * graph_height];
+    let mut frontier: Vec<(usize, usize)> = Vec::new();
+
+    let neighbours = |x: usize, y: usize| {
+        [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .filter(move |&(nx, ny)| nx >= 1 && ny >= 1 && nx < graph_width && ny < graph_height)
+    };
+
+    let start = (
+        1 + rng.below(graph_width as u32 - 1) as usize,
+        1 + rng.below(graph_height as u32 - 1) as usize,
+    );
+    let mut next = Some(start);
+    while let Some((x, y)) = next {
+        in_maze[y * graph_width + x] = true;
+        for (nx, ny) in neighbours(x, y) {
+            let index = ny * graph_width + nx;
+            if !in_maze[index] && !queued[index] {
+                queued[index] = true;
+                frontier.push((nx, ny));
+            }
+        }
+        if frontier.is_empty() {
+            break;
+        }
+        let (fx, fy) = frontier.swap_remove(rng.below(frontier.len() as u32) as usize);
+        // Connect the frontier field to one of its fields in the maze.
+        let mut connected: Vec<(usize, usize)> = neighbours(fx, fy)
+            .filter(|&(nx, ny)| in_maze[ny * graph_width + nx])
+            .collect();
+        let (nx, ny) = connected.swap_remove(rng.below(connected.len() as u32) as usize);
+        // Store the passage on the field with the smaller coordinate.
+        let (px, py) = (fx.min(nx), fy.min(ny));
+        let passage = &mut passages[py * graph_width + px];
+        if fx != nx {
+            passage.0 = true;
+        } else {
+            passage.1 = true;
+        }
+        next = Some((fx, fy));
+    }
+    passages
+}
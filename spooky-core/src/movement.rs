@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+
+use bevy_math::ops;
+
+use crate::maze::Maze;
+
+/// Rules applied by `resolve_step` when a move is checked against the maze walls.
+#[derive(Resource, Clone, Debug)]
+pub struct MovementRules {
+    /// Let a diagonal step pass between two walls that touch at a corner.
+    /// When disabled, both tiles next to the diagonal must be free.
+    pub allow_corner_cutting: bool,
+    /// When a diagonal step is blocked, apply the axis that is still free.
+    pub wall_sliding: bool,
+}
+
+impl Default for MovementRules {
+    fn default() -> Self {
+        Self {
+            allow_corner_cutting: false,
+            wall_sliding: true,
+        }
+    }
+}
+
+/// Resolve a movement request from `(x, y)` by `(dx, dy)` in world pixels.
+///
+/// Straight moves only check the destination tile. Diagonal moves additionally check the
+/// two tiles reached by each axis step alone, so that the player cannot squeeze through a
+/// wall corner unless `allow_corner_cutting` is set. A blocked diagonal slides along the
+/// wall when `wall_sliding` is set, preferring the horizontal axis. Returns the new
+/// position, or `None` if the player cannot move at all.
+pub fn resolve_step(
+    maze: &Maze,
+    x: f32,
+    y: f32,
+    dx: f32,
+    dy: f32,
+    rules: &MovementRules,
+) -> Option<(f32, f32)> {
+    let is_free = |cx: f32, cy: f32| !maze.check_wall_collision(cx as i32, cy as i32);
+
+    if dx == 0.0 || dy == 0.0 {
+        return is_free(x + dx, y + dy).then_some((x + dx, y + dy));
+    }
+
+    let x_free = is_free(x + dx, y);
+    let y_free = is_free(x, y + dy);
+    let diagonal_free =
+        is_free(x + dx, y + dy) && (rules.allow_corner_cutting || (x_free && y_free));
+    if diagonal_free {
+        return Some((x + dx, y + dy));
+    }
+
+    if rules.wall_sliding {
+        if x_free {
+            return Some((x + dx, y));
+        }
+        if y_free {
+            return Some((x, y + dy));
+        }
+    }
+    None
+}
+
+/// How the player moves through the maze.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Default)]
+pub enum MovementMode {
+    /// One tile per input step, checked with `resolve_step`.
+    #[default]
+    Grid,
+    /// Free movement at a speed proportional to the input magnitude.
+    /// `max_speed` is in pixels per second at full deflection.
+    Continuous { max_speed: f32 },
+}
+
+/// Default `max_speed` of continuous mode: five tiles per second.
+pub const DEFAULT_CONTINUOUS_SPEED: f32 = 80.0;
+
+/// Gap between the player's collision box and the edges of its tile in continuous mode.
+/// The smaller box lets the player enter one-tile corridors without pixel-perfect aim.
+pub const HITBOX_INSET: f32 = 3.0;
+
+/// Keeps a box that ends exactly on a tile boundary out of the next tile.
+/// `f32::EPSILON` would vanish at world coordinates of a few hundred pixels.
+const EDGE_EPSILON: f32 = 0.001;
+
+/// Axis-aligned box in world pixels, `min` inclusive and `max` exclusive.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl Aabb {
+    /// The box of a whole tile whose corner is at `(x, y)`.
+    pub fn tile(maze: &Maze, x: f32, y: f32) -> Self {
+        Self {
+            min_x: x,
+            min_y: y,
+            max_x: x + maze.tile_width as f32,
+            max_y: y + maze.tile_height as f32,
+        }
+    }
+
+    /// The player's collision box when its sprite corner is at `(x, y)`.
+    pub fn player(maze: &Maze, x: f32, y: f32) -> Self {
+        Self {
+            min_x: x + HITBOX_INSET,
+            min_y: y + HITBOX_INSET,
+            max_x: x + maze.tile_width as f32 - HITBOX_INSET,
+            max_y: y + maze.tile_height as f32 - HITBOX_INSET,
+        }
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min_x < other.max_x
+            && other.min_x < self.max_x
+            && self.min_y < other.max_y
+            && other.min_y < self.max_y
+    }
+
+    /// True if any wall tile (or the area outside the maze) intersects the box.
+    pub fn hits_wall(&self, maze: &Maze) -> bool {
+        let tile_w = maze.tile_width as f32;
+        let tile_h = maze.tile_height as f32;
+        let first_tx = ops::floor(self.min_x / tile_w) as i32;
+        let last_tx = ops::floor((self.max_x - EDGE_EPSILON) / tile_w) as i32;
+        let first_ty = ops::floor(self.min_y / tile_h) as i32;
+        let last_ty = ops::floor((self.max_y - EDGE_EPSILON) / tile_h) as i32;
+        for ty in first_ty..=last_ty {
+            for tx in first_tx..=last_tx {
+                if maze.check_wall_collision(tx * tile_w as i32, ty * tile_h as i32) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// True if the player at `(player_x, player_y)` picks up an item lying on the tile at
+/// `(item_x, item_y)`. Grid mode keeps the exact tile match; continuous mode uses overlap.
+pub fn touches_item(
+    mode: MovementMode,
+    maze: &Maze,
+    player_x: f32,
+    player_y: f32,
+    item_x: i32,
+    item_y: i32,
+) -> bool {
+    match mode {
+        MovementMode::Grid => item_x == player_x as i32 && item_y == player_y as i32,
+        MovementMode::Continuous { .. } => Aabb::player(maze, player_x, player_y)
+            .overlaps(&Aabb::tile(maze, item_x as f32, item_y as f32)),
+    }
+}
+
+/// Move the player's collision box by `(dx, dy)`, one axis at a time, stopping flush
+/// against walls. The move is split into sub-steps shorter than a tile, so fast
+/// movement cannot tunnel through a wall. Returns the new sprite corner.
+pub fn move_aabb(maze: &Maze, x: f32, y: f32, dx: f32, dy: f32) -> (f32, f32) {
+    let max_step = (maze.tile_width.min(maze.tile_height) as f32) / 2.0;
+    let steps = ops::ceil(dx.abs().max(dy.abs()) / max_step).max(1.0);
+    let (step_x, step_y) = (dx / steps, dy / steps);
+
+    let (mut x, mut y) = (x, y);
+    for _ in 0..steps as u32 {
+        x = sweep_axis(maze, x, y, step_x, true);
+        y = sweep_axis(maze, x, y, step_y, false);
+    }
+    (x, y)
+}
+
+fn sweep_axis(maze: &Maze, x: f32, y: f32, delta: f32, horizontal: bool) -> f32 {
+    if delta == 0.0 {
+        return if horizontal { x } else { y };
+    }
+    let (start, tile) = if horizontal {
+        (x, maze.tile_width as f32)
+    } else {
+        (y, maze.tile_height as f32)
+    };
+    let candidate = start + delta;
+    let hits = |pos: f32| {
+        let aabb = if horizontal {
+            Aabb::player(maze, pos, y)
+        } else {
+            Aabb::player(maze, x, pos)
+        };
+        aabb.hits_wall(maze)
+    };
+    if !hits(candidate) {
+        return candidate;
+    }
+    // Snap the leading edge of the box to the boundary of the tile it entered.
+    let flush = if delta > 0.0 {
+        let leading = candidate + tile - HITBOX_INSET;
+        ops::floor(leading / tile) * tile - tile + HITBOX_INSET
+    } else {
+        let leading = candidate + HITBOX_INSET;
+        ops::floor(leading / tile) * tile + tile - HITBOX_INSET
+    };
+    if (flush - start) * delta >= 0.0 && !hits(flush) {
+        flush
+    } else {
+        start
+    }
+}
@@ -0,0 +1,192 @@
+//! Dirty-rectangle tracking for the embedded renderer.
+//!
+//! The frame is still rendered completely into the framebuffer, which is cheap, but
+//! only the regions that differ from the previous frame are pushed to the display,
+//! which is the slow part over SPI. A region is dirty when a sprite appeared,
+//! disappeared or moved, a tile changed (dynamite) or the HUD text changed. When the
+//! camera moves or the zoom changes every pixel changes, so the whole frame is flushed.
+
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics_framebuf::FrameBuf;
+use embedded_graphics_framebuf::backends::FrameBufferBackend;
+use heapless::Vec;
+
+use crate::maze::Maze;
+use crate::render::FrameSummary;
+use crate::systems::hud::HudState;
+
+/// Regions kept before giving up and flushing the whole frame.
+pub const MAX_DIRTY_REGIONS: usize = 32;
+
+/// What has to be sent to the display after a frame.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Flush {
+    /// Nothing changed.
+    None,
+    /// Send the whole framebuffer.
+    Full,
+    /// Send only these non-overlapping screen regions.
+    Regions(Vec<Rectangle, MAX_DIRTY_REGIONS>),
+}
+
+/// Remembers the previous frame to work out what changed.
+pub struct DirtyTracker {
+    previous: Option<(FrameSummary, HudState)>,
+    tiles: [u8; 64 * 64],
+}
+
+impl Default for DirtyTracker {
+    fn default() -> Self {
+        Self {
+            previous: None,
+            tiles: [0; 64 * 64],
+        }
+    }
+}
+
+impl DirtyTracker {
+    /// Compare the frame just rendered with the previous one and decide what to flush.
+    pub fn update(&mut self, summary: &FrameSummary, maze: &Maze, hud_state: &HudState) -> Flush {
+        let tiles_before = core::mem::replace(&mut self.tiles, maze.data);
+        let Some((previous, previous_hud)) =
+            self.previous.replace((summary.clone(), hud_state.clone()))
+        else {
+            return Flush::Full;
+        };
+        if previous.view != summary.view || previous.overflow || summary.overflow {
+            return Flush::Full;
+        }
+
+        let mut regions = Regions::new(summary.view.viewport);
+        for sprite in previous.sprites.iter() {
+            if !summary.sprites.contains(sprite) {
+                regions.add(sprite.1);
+            }
+        }
+        for sprite in summary.sprites.iter() {
+            if !previous.sprites.contains(sprite) {
+                regions.add(sprite.1);
+            }
+        }
+        if previous_hud != *hud_state || previous.hud != summary.hud {
+            regions.add(previous.hud);
+            regions.add(summary.hud);
+        }
+
+        let (maze_left, maze_bottom, _maze_right, _maze_top) = maze.playable_bounds();
+        let tile_size = Size::new(maze.tile_width, maze.tile_height);
+        let tile_count = (maze.width * maze.height) as usize;
+        for index in 0..tile_count {
+            if tiles_before[index] != maze.data[index] {
+                let tx = (index % maze.width as usize) as i32;
+                let ty = (index / maze.width as usize) as i32;
+                let world = Point::new(
+                    maze_left + tx * tile_size.width as i32,
+                    maze_bottom + ty * tile_size.height as i32,
+                );
+                regions.add(summary.view.screen_rect(world, tile_size));
+            }
+        }
+
+        regions.finish()
+    }
+}
+
+/// Send the regions selected by `DirtyTracker::update` from the framebuffer to the
+/// display. Each region is written with `fill_contiguous`, which display drivers such
+/// as mipidsi turn into a single `set_pixels` address window.
+pub fn flush<D, B>(
+    display: &mut D,
+    frame: &FrameBuf<D::Color, B>,
+    flush: &Flush,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    B: FrameBufferBackend<Color = D::Color>,
+{
+    match flush {
+        Flush::None => Ok(()),
+        Flush::Full => {
+            let area = Rectangle::new(Point::zero(), frame.size());
+            display.fill_contiguous(&area, frame.into_iter().map(|pixel| pixel.1))
+        }
+        Flush::Regions(regions) => {
+            for region in regions {
+                let colors = region.points().map(|point| frame.get_color_at(point));
+                display.fill_contiguous(region, colors)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Smallest rectangle containing both `a` and `b`; zero-sized rectangles are ignored.
+pub fn union(a: Rectangle, b: Rectangle) -> Rectangle {
+    if a.is_zero_sized() {
+        return b;
+    }
+    if b.is_zero_sized() {
+        return a;
+    }
+    let (Some(a_end), Some(b_end)) = (a.bottom_right(), b.bottom_right()) else {
+        return a;
+    };
+    let top_left = a.top_left.component_min(b.top_left);
+    let bottom_right = a_end.component_max(b_end);
+    Rectangle::with_corners(top_left, bottom_right)
+}
+
+/// Collects dirty regions, merging overlapping ones, and falls back to a full flush
+/// when there are too many of them or they cover half of the screen anyway.
+struct Regions {
+    viewport: Size,
+    regions: Vec<Rectangle, MAX_DIRTY_REGIONS>,
+    overflow: bool,
+}
+
+impl Regions {
+    fn new(viewport: Size) -> Self {
+        Self {
+            viewport,
+            regions: Vec::new(),
+            overflow: false,
+        }
+    }
+
+    fn add(&mut self, region: Rectangle) {
+        let mut region = region.intersection(&Rectangle::new(Point::zero(), self.viewport));
+        if region.is_zero_sized() {
+            return;
+        }
+        // Absorb every region that overlaps the new one, so the list stays disjoint
+        // and no pixel is sent twice.
+        while let Some(index) = self
+            .regions
+            .iter()
+            .position(|existing| !existing.intersection(&region).is_zero_sized())
+        {
+            region = union(region, self.regions.swap_remove(index));
+        }
+        self.overflow |= self.regions.push(region).is_err();
+    }
+
+    fn finish(self) -> Flush {
+        if self.overflow {
+            return Flush::Full;
+        }
+        if self.regions.is_empty() {
+            return Flush::None;
+        }
+        let area: u32 = self
+            .regions
+            .iter()
+            .map(|region| region.size.width * region.size.height)
+            .sum();
+        if area * 2 >= self.viewport.width * self.viewport.height {
+            Flush::Full
+        } else {
+            Flush::Regions(self.regions)
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Embedded Graphics implementation of the HUD described by `HudLayout`.
+//!
+//! Icons are the scene's sprites, drawn at half size when the widget is lower than
+//! the icon. Text and bars are drawn in the display's colour, converted from
+//! `Rgb565`. Every widget is clipped to its area, so the HUD never covers more than
+//! the layout says, which keeps the dirty tracking exact.
+
+use core::fmt::Write;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::mono_font::ascii::FONT_6X10;
+use embedded_graphics::pixelcolor::{Rgb565, WebColors};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Baseline, Text};
+use heapless::String;
+
+use crate::hud_layout::{HudLayout, HudWidget, WidgetLayout, WidgetStyle};
+use crate::render::Sprites;
+use crate::render::dirty;
+use crate::render::zoom::Zoom;
+use crate::systems::hud::HudState;
+use crate::systems::setup::TextureId;
+
+/// Gap between an icon and whatever follows it.
+const ICON_GAP: i32 = 2;
+
+/// Draw all widgets of `layout` and return the area they cover.
+pub fn draw_hud<D, S>(
+    target: &mut D,
+    layout: &HudLayout,
+    hud_state: &HudState,
+    sprites: &S,
+) -> Result<Rectangle, D::Error>
+where
+    D: DrawTarget<Color: From<Rgb565>>,
+    S: Sprites<D::Color>,
+{
+    let mut area = Rectangle::zero();
+    for widget in layout.widgets {
+        draw_widget(
+            &mut target.clipped(&widget.area),
+            widget,
+            hud_state,
+            sprites,
+        )?;
+        area = dirty::union(area, widget.area);
+    }
+    Ok(area)
+}
+
+fn draw_widget<D, S>(
+    target: &mut D,
+    layout: &WidgetLayout,
+    hud_state: &HudState,
+    sprites: &S,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color: From<Rgb565>>,
+    S: Sprites<D::Color>,
+{
+    let value = layout.widget.value(hud_state);
+    let area = layout.area;
+    let icon = icon(layout.widget, sprites);
+    match (layout.style, icon) {
+        (WidgetStyle::IconCount, Some(icon)) => {
+            let width = draw_icon(target, sprites, icon, area.top_left, area.size.height)?;
+            let mut text: String<16> = String::new();
+            let _ = write!(text, "{}", value);
+            draw_text(
+                target,
+                &text,
+                area.top_left + Point::new(width as i32 + ICON_GAP, 0),
+                area.size.height,
+            )
+        }
+        (WidgetStyle::Icons, Some(icon)) => {
+            let (_, size) = icon;
+            let step = icon_zoom(size, area.size.height).scale(size.width as i32) + 1;
+            let fits = (area.size.width as i32 / step) as u32;
+            for index in 0..value.min(fits) {
+                let position = area.top_left + Point::new(index as i32 * step, 0);
+                draw_icon(target, sprites, icon, position, area.size.height)?;
+            }
+            Ok(())
+        }
+        (WidgetStyle::Bar, _) if layout.widget.maximum().is_some() => {
+            draw_bar(target, layout.widget, area, value)
+        }
+        _ => {
+            let mut text: String<32> = String::new();
+            let _ = write!(text, "{}: ", layout.widget.label());
+            let _ = layout.widget.write_value(&mut text, hud_state);
+            if text.len() as u32 * FONT_6X10.character_size.width > area.size.width {
+                text.clear();
+                let _ = write!(text, "{}", layout.widget.short_label());
+                let _ = layout.widget.write_value(&mut text, hud_state);
+            }
+            draw_text(target, &text, area.top_left, area.size.height)
+        }
+    }
+}
+
+/// The loaded sprite that stands for a widget and its size, if any.
+fn icon<C: PixelColor>(widget: HudWidget, sprites: &impl Sprites<C>) -> Option<(TextureId, Size)> {
+    let texture = match widget {
+        HudWidget::Coins => TextureId::Coin,
+        HudWidget::Teleport => TextureId::Ghost,
+        HudWidget::WalkerTimer => TextureId::Walker,
+        HudWidget::Dynamite => TextureId::Dynamite,
+        HudWidget::Level | HudWidget::Seed => return None,
+    };
+    sprites.size(texture).map(|size| (texture, size))
+}
+
+fn icon_zoom(size: Size, height: u32) -> Zoom {
+    if size.height <= height {
+        Zoom::Normal
+    } else {
+        Zoom::Half
+    }
+}
+
+/// Draw an icon without its transparent pixels and return its width on screen.
+fn draw_icon<D, S>(
+    target: &mut D,
+    sprites: &S,
+    (texture, size): (TextureId, Size),
+    position: Point,
+    height: u32,
+) -> Result<u32, D::Error>
+where
+    D: DrawTarget,
+    S: Sprites<D::Color>,
+{
+    let zoom = icon_zoom(size, height);
+    sprites.draw(target, texture, position, zoom)?;
+    Ok(zoom.scale_size(size).width)
+}
+
+/// Draw a line of text vertically centred in a widget of `height`.
+fn draw_text<D>(target: &mut D, text: &str, position: Point, height: u32) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color: From<Rgb565>>,
+{
+    let text_style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE.into());
+    let top = (height as i32 - FONT_6X10.character_size.height as i32).max(0) / 2;
+    Text::with_baseline(
+        text,
+        position + Point::new(0, top),
+        text_style,
+        Baseline::Top,
+    )
+    .draw(target)?;
+    Ok(())
+}
+
+/// Draw a bar filled up to `value / maximum`. Bars of at least 5 pixels get an
+/// outline; thinner ones are only the fill on a dark track.
+fn draw_bar<D>(
+    target: &mut D,
+    widget: HudWidget,
+    area: Rectangle,
+    value: u32,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color: From<Rgb565>>,
+{
+    let maximum = widget.maximum().unwrap_or(1).max(1);
+    let color = match widget {
+        HudWidget::WalkerTimer => Rgb565::GREEN,
+        _ => Rgb565::CYAN,
+    };
+    let inner = if area.size.height >= 5 {
+        area.into_styled(PrimitiveStyle::with_stroke(Rgb565::WHITE.into(), 1))
+            .draw(target)?;
+        area.offset(-1)
+    } else {
+        area.into_styled(PrimitiveStyle::with_fill(
+            Rgb565::CSS_DARK_SLATE_GRAY.into(),
+        ))
+        .draw(target)?;
+        area
+    };
+    let filled = inner.size.width * value.min(maximum) / maximum;
+    Rectangle::new(inner.top_left, Size::new(filled, inner.size.height))
+        .into_styled(PrimitiveStyle::with_fill(color.into()))
+        .draw(target)
+}
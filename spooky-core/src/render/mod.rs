@@ -0,0 +1,416 @@
+//! Renderer shared by the embedded boards.
+//!
+//! `render_frame` draws the maze, the sprites and the HUD into any `DrawTarget` whose
+//! colour converts from `Rgb565`, usually a framebuffer in the display's own pixel
+//! format. The viewport is the size of the target, so the same code serves every
+//! display; the boards only own the display and flush the finished frame to it.
+//! `render_frame_cached` takes the textures from a `TileCache`, which converted them
+//! with the display's `Palette` when it decoded them.
+
+pub mod dirty;
+pub mod hud;
+pub mod pixel_format;
+pub mod tile_cache;
+pub mod zoom;
+
+use embedded_graphics::image::Image;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::mono_font::ascii::FONT_6X10;
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Alignment, Text};
+use embedded_graphics_framebuf::FrameBuf;
+use heapless::Vec;
+use tinybmp::Bmp;
+
+use crate::heapbuffer::{HeapBuffer, VecBuffer};
+use crate::hud_layout::HudLayout;
+use crate::maze::{Coin, Maze};
+use crate::render::tile_cache::TileCache;
+use crate::render::zoom::{Zoom, Zoomed};
+use crate::resources::PlayerPosition;
+use crate::sprite_buf::SpriteBuf;
+use crate::systems::hud::HudState;
+use crate::systems::setup::{NoStdTransform, TextureAssets, TextureId};
+use crate::touch::TouchMapper;
+
+/// Where the scene and the HUD take their sprites from: the BMPs of `TextureAssets`,
+/// decoded for every frame and converted directly, or the sprites a `TileCache`
+/// decoded once in the display's colours.
+pub trait Sprites<C: PixelColor> {
+    /// Size of `texture` in world pixels, `None` when it is not loaded.
+    fn size(&self, texture: TextureId) -> Option<Size>;
+
+    /// Draw `texture` scaled by `zoom` without its transparent pixels, with its
+    /// top-left corner at the screen position `screen`.
+    fn draw<D>(
+        &self,
+        target: &mut D,
+        texture: TextureId,
+        screen: Point,
+        zoom: Zoom,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>;
+}
+
+impl<C: PixelColor + From<Rgb565>> Sprites<C> for TextureAssets {
+    fn size(&self, texture: TextureId) -> Option<Size> {
+        self.sprite(texture).map(|bmp| bmp.size())
+    }
+
+    fn draw<D>(
+        &self,
+        target: &mut D,
+        texture: TextureId,
+        screen: Point,
+        zoom: Zoom,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let Some(bmp) = self.sprite(texture) else {
+            return Ok(());
+        };
+        let mut target = target.color_converted::<Rgb565>();
+        let mut sprite_buf = SpriteBuf::with_key(&mut target, self.transparency_key);
+        draw_image(&mut sprite_buf, bmp, screen, zoom)
+    }
+}
+
+/// Upper bound of sprites recorded per frame: all coins, walkers, dynamite, NPCs and the ghost.
+pub const MAX_SPRITES: usize = 128;
+
+/// What `render_frame` put on the screen, in screen coordinates. `DirtyTracker`
+/// compares two consecutive summaries to find the regions that need a flush.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameSummary {
+    pub view: View,
+    /// Visible sprites with the texture they show.
+    pub sprites: Vec<(TextureId, Rectangle), MAX_SPRITES>,
+    /// Area covered by the HUD widgets.
+    pub hud: Rectangle,
+    /// More sprites were visible than `MAX_SPRITES`; the summary is incomplete.
+    pub overflow: bool,
+}
+
+impl FrameSummary {
+    fn new(view: View) -> Self {
+        Self {
+            view,
+            sprites: Vec::new(),
+            hud: Rectangle::zero(),
+            overflow: false,
+        }
+    }
+
+    fn record(&mut self, texture: TextureId, area: Option<Rectangle>) {
+        if let Some(area) = area {
+            self.overflow |= self.sprites.push((texture, area)).is_err();
+        }
+    }
+}
+
+/// Screen-independent position of a sprite: its (possibly interpolated) transform.
+pub fn sprite_position(transform: &NoStdTransform) -> Point {
+    Point::new(
+        transform.0.translation.x as i32,
+        transform.0.translation.y as i32,
+    )
+}
+
+/// World position the viewport is centred on: the player's visual position when its
+/// transform is available (it glides between tiles, so the maze scrolls by sub-tile
+/// offsets), otherwise the logical position.
+pub fn camera_position(player: Option<&NoStdTransform>, player_pos: &PlayerPosition) -> Point {
+    match player {
+        Some(transform) => sprite_position(transform),
+        None => Point::new(player_pos.x as i32, player_pos.y as i32),
+    }
+}
+
+/// How the world is mapped onto the render target in one frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct View {
+    /// World position shown in the centre of the viewport.
+    pub camera: Point,
+    pub zoom: Zoom,
+    /// Size of the render target.
+    pub viewport: Size,
+}
+
+impl View {
+    pub fn to_screen(&self, world: Point) -> Point {
+        self.zoom.to_screen(world, self.camera, self.viewport)
+    }
+
+    /// Screen area covered by something of `size` world pixels at `world`.
+    pub fn screen_rect(&self, world: Point, size: Size) -> Rectangle {
+        Rectangle::new(self.to_screen(world), self.zoom.scale_size(size))
+    }
+}
+
+/// Render one frame centred on `camera`: the maze background first, then the sprites
+/// without their transparent pixels, then the HUD overlay.
+///
+/// Sprites are drawn from back to front: coins, walkers, dynamite, NPCs and finally the
+/// ghost, matching the z values used by the std build. `npcs` are the NPCs' visual
+/// positions, which glide between tiles like the ghost. Sprites outside the viewport
+/// are skipped. The world is scaled by `zoom`; the HUD is not. The returned summary
+/// describes what was drawn, see `dirty`.
+pub fn render_frame<D>(
+    target: &mut D,
+    maze: &Maze,
+    texture_assets: &TextureAssets,
+    camera: Point,
+    zoom: Zoom,
+    npcs: impl IntoIterator<Item = Point>,
+    hud_state: &HudState,
+) -> Result<FrameSummary, D::Error>
+where
+    D: DrawTarget<Color: From<Rgb565>>,
+{
+    render_scene(
+        target,
+        maze,
+        texture_assets,
+        camera,
+        zoom,
+        npcs,
+        hud_state,
+        |target, view| {
+            target.clear(Rgb565::BLACK.into())?;
+            draw_maze(target, maze, texture_assets, view)
+        },
+    )
+}
+
+/// Same as `render_frame`, but the textures come from a `TileCache` instead of being
+/// decoded from the BMPs for every frame: the background tiles are copied row by row
+/// and the sprites are drawn already scaled and in the display's colours. The zoom is
+/// the one the cache was built for.
+pub fn render_frame_cached<F>(
+    frame: &mut F,
+    tile_cache: &TileCache<F::Color>,
+    maze: &Maze,
+    camera: Point,
+    npcs: impl IntoIterator<Item = Point>,
+    hud_state: &HudState,
+) -> Result<FrameSummary, F::Error>
+where
+    F: RawFramebuffer<Color: From<Rgb565>>,
+{
+    render_scene(
+        frame,
+        maze,
+        tile_cache,
+        camera,
+        tile_cache.zoom(),
+        npcs,
+        hud_state,
+        |frame, view| {
+            let pixels = frame.pixels_mut();
+            pixels.fill(Rgb565::BLACK.into());
+            tile_cache.draw_maze(pixels, view, maze);
+            Ok(())
+        },
+    )
+}
+
+/// Framebuffers that expose their pixels as one row-major slice, which lets the tile
+/// cache blit with plain memory copies.
+pub trait RawFramebuffer: DrawTarget {
+    fn pixels_mut(&mut self) -> &mut [Self::Color];
+}
+
+impl<C: PixelColor, const N: usize> RawFramebuffer for FrameBuf<C, HeapBuffer<C, N>> {
+    fn pixels_mut(&mut self) -> &mut [C] {
+        &mut self.data[..]
+    }
+}
+
+impl<C: PixelColor> RawFramebuffer for FrameBuf<C, VecBuffer<C>> {
+    fn pixels_mut(&mut self) -> &mut [C] {
+        &mut self.data[..]
+    }
+}
+
+/// Shared part of the render paths: `background` clears the target and draws the
+/// tiles, everything on top of it is the same for every path.
+#[allow(clippy::too_many_arguments)]
+fn render_scene<D, S, B>(
+    target: &mut D,
+    maze: &Maze,
+    sprites: &S,
+    camera: Point,
+    zoom: Zoom,
+    npcs: impl IntoIterator<Item = Point>,
+    hud_state: &HudState,
+    background: B,
+) -> Result<FrameSummary, D::Error>
+where
+    D: DrawTarget<Color: From<Rgb565>>,
+    S: Sprites<D::Color>,
+    B: FnOnce(&mut D, &View) -> Result<(), D::Error>,
+{
+    let view = View {
+        camera,
+        zoom,
+        viewport: target.bounding_box().size,
+    };
+    let mut summary = FrameSummary::new(view);
+
+    background(target, &view)?;
+
+    let layers: [(TextureId, &[Coin]); 3] = [
+        (TextureId::Coin, &maze.coins),
+        (TextureId::Walker, &maze.walkers),
+        (TextureId::Dynamite, &maze.dynamites),
+    ];
+    for (texture, items) in layers {
+        for item in items.iter().filter(|item| item.x != -1 && item.y != -1) {
+            let area = draw_sprite(target, sprites, texture, Point::new(item.x, item.y), &view)?;
+            summary.record(texture, area);
+        }
+    }
+    for npc in npcs {
+        let area = draw_sprite(target, sprites, TextureId::Npc, npc, &view)?;
+        summary.record(TextureId::Npc, area);
+    }
+    let area = draw_sprite(target, sprites, TextureId::Ghost, camera, &view)?;
+    summary.record(TextureId::Ghost, area);
+
+    summary.hud = hud::draw_hud(
+        target,
+        &HudLayout::for_display(view.viewport),
+        hud_state,
+        sprites,
+    )?;
+    Ok(summary)
+}
+
+/// Draw a sprite at a world position unless it is not loaded or lies completely
+/// outside the viewport. Returns the visible part of the sprite.
+fn draw_sprite<D, S>(
+    target: &mut D,
+    sprites: &S,
+    texture: TextureId,
+    world: Point,
+    view: &View,
+) -> Result<Option<Rectangle>, D::Error>
+where
+    D: DrawTarget,
+    S: Sprites<D::Color>,
+{
+    let Some(size) = sprites.size(texture) else {
+        return Ok(None);
+    };
+    let area = view.screen_rect(world, size);
+    let visible = area.intersection(&Rectangle::new(Point::zero(), view.viewport));
+    if visible.is_zero_sized() {
+        return Ok(None);
+    }
+    sprites.draw(target, texture, area.top_left, view.zoom)?;
+    Ok(Some(visible))
+}
+
+/// Draw a bitmap with its top-left corner at a screen position, scaled by `zoom`.
+fn draw_image<D>(
+    target: &mut D,
+    bmp: &Bmp<'static, Rgb565>,
+    screen: Point,
+    zoom: Zoom,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    if zoom == Zoom::Normal {
+        return Image::new(bmp, screen).draw(target);
+    }
+    Image::new(bmp, Point::zero()).draw(&mut Zoomed {
+        target,
+        zoom,
+        origin: screen,
+    })
+}
+
+/// Draw the tiles that intersect the viewport.
+fn draw_maze<D>(
+    target: &mut D,
+    maze: &Maze,
+    texture_assets: &TextureAssets,
+    view: &View,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color: From<Rgb565>>,
+{
+    let mut target = target.color_converted::<Rgb565>();
+    for (tile, screen) in visible_tiles(maze, view) {
+        let bmp = match tile {
+            1 => texture_assets.wall.as_ref(),
+            2 => texture_assets.scorched.as_ref(),
+            _ => texture_assets.ground.as_ref(),
+        };
+        if let Some(bmp) = bmp {
+            draw_image(&mut target, bmp, screen, view.zoom)?;
+        }
+    }
+    Ok(())
+}
+
+/// The maze tiles that intersect the viewport, with the screen position of their
+/// top-left corner. Works for any tile size and zoom.
+pub(crate) fn visible_tiles<'a>(
+    maze: &'a Maze,
+    view: &View,
+) -> impl Iterator<Item = (u8, Point)> + 'a {
+    let (maze_left, maze_bottom, _maze_right, _maze_top) = maze.playable_bounds();
+    let tile_w = view.zoom.scale(maze.tile_width as i32).max(1);
+    let tile_h = view.zoom.scale(maze.tile_height as i32).max(1);
+    // Screen position of the maze's first tile; every other tile is a multiple of the
+    // scaled tile size away from it.
+    let origin = view.to_screen(Point::new(maze_left, maze_bottom));
+
+    // Compute visible tile indices (clamped to maze dimensions).
+    let min_tx = (-origin.x).div_euclid(tile_w).max(0);
+    let max_tx = (view.viewport.width as i32 - 1 - origin.x)
+        .div_euclid(tile_w)
+        .min(maze.width as i32 - 1);
+    let min_ty = (-origin.y).div_euclid(tile_h).max(0);
+    let max_ty = (view.viewport.height as i32 - 1 - origin.y)
+        .div_euclid(tile_h)
+        .min(maze.height as i32 - 1);
+
+    (min_ty..=max_ty).flat_map(move |ty| {
+        (min_tx..=max_tx).map(move |tx| {
+            // The maze data is stored in row‑major order (with row 0 at the top).
+            let tile_index = (ty * maze.width as i32 + tx) as usize;
+            let screen = origin + Point::new(tx * tile_w, ty * tile_h);
+            (maze.data[tile_index], screen)
+        })
+    })
+}
+
+/// Draw the on-screen touch buttons (teleport bottom-left, dynamite bottom-right).
+pub fn draw_touch_buttons<D>(target: &mut D, mapper: &TouchMapper) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color: From<Rgb565>>,
+{
+    let text_style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE.into());
+    let button_style = PrimitiveStyle::with_stroke(Rgb565::WHITE.into(), 1);
+    for (button, label) in [
+        (mapper.teleport_button(), "TP"),
+        (mapper.dynamite_button(), "DYN"),
+    ] {
+        button.into_styled(button_style).draw(target)?;
+        Text::with_alignment(
+            label,
+            button.center() + Point::new(0, 3),
+            text_style,
+            Alignment::Center,
+        )
+        .draw(target)?;
+    }
+    Ok(())
+}
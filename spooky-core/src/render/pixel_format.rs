@@ -0,0 +1,96 @@
+//! Displays with other pixel formats.
+//!
+//! The textures are `Rgb565` BMPs; everything else is drawn in the display's own
+//! colour, so the framebuffer of an RGB888 panel or a 1-bit OLED is flushed as is.
+//! A `Palette` converts the textures once, when `TileCache` decodes them. The
+//! conversion gets the position of every pixel in its texture, which lets
+//! `Monochrome` dither with an ordered pattern that moves with the sprite or tile.
+//! Text and bars of the HUD use the plain `From<Rgb565>` conversion.
+
+use embedded_graphics::pixelcolor::{BinaryColor, Rgb565, Rgb888};
+use embedded_graphics::prelude::*;
+
+/// Maps `Rgb565` texture pixels to the colour of a display.
+pub trait Palette<C: PixelColor> {
+    fn convert(&self, color: Rgb565, point: Point) -> C;
+}
+
+/// Plain colour conversion, for displays in `Rgb565`, `Rgb888` or another RGB format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Direct;
+
+impl<C: PixelColor + From<Rgb565>> Palette<C> for Direct {
+    fn convert(&self, color: Rgb565, _point: Point) -> C {
+        color.into()
+    }
+}
+
+/// An optional palette; `None` converts directly.
+impl<C: PixelColor + From<Rgb565>, P: Palette<C>> Palette<C> for Option<P> {
+    fn convert(&self, color: Rgb565, point: Point) -> C {
+        match self {
+            Some(palette) => palette.convert(color, point),
+            None => color.into(),
+        }
+    }
+}
+
+/// How colours are reduced to black and white.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tone {
+    /// Pixels brighter than the given luma (0-255) are on.
+    Threshold(u8),
+    /// 4x4 ordered dithering, which keeps the textures recognisable.
+    Dither,
+}
+
+impl Default for Tone {
+    fn default() -> Self {
+        Tone::Threshold(96)
+    }
+}
+
+/// 4x4 Bayer matrix, the order in which pixels of a cell turn on as brightness rises.
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+impl Tone {
+    /// Whether a pixel of `color` at `point` is on.
+    pub fn is_on(self, color: Rgb565, point: Point) -> bool {
+        let level = match self {
+            Tone::Threshold(level) => level,
+            Tone::Dither => {
+                let cell =
+                    BAYER_4X4[point.y.rem_euclid(4) as usize][point.x.rem_euclid(4) as usize];
+                cell * 16 + 8
+            }
+        };
+        luma(color) > level
+    }
+}
+
+/// Perceived brightness of a colour, 0-255.
+pub fn luma(color: Rgb565) -> u8 {
+    let color = Rgb888::from(color);
+    ((color.r() as u32 * 77 + color.g() as u32 * 150 + color.b() as u32 * 29) >> 8) as u8
+}
+
+/// Palette of 1-bit displays such as the SSD1306.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Monochrome(pub Tone);
+
+impl Palette<BinaryColor> for Monochrome {
+    fn convert(&self, color: Rgb565, point: Point) -> BinaryColor {
+        BinaryColor::from(self.0.is_on(color, point))
+    }
+}
+
+/// Black and white on a colour display, to preview a monochrome board.
+impl Palette<Rgb565> for Monochrome {
+    fn convert(&self, color: Rgb565, point: Point) -> Rgb565 {
+        if self.0.is_on(color, point) {
+            Rgb565::WHITE
+        } else {
+            Rgb565::BLACK
+        }
+    }
+}
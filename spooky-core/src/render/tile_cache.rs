@@ -0,0 +1,225 @@
+//! Decoded copies of the maze tiles and sprites.
+//!
+//! Drawing a `tinybmp::Bmp` through `Image` decodes it pixel by pixel for every
+//! frame. The cache decodes each texture once into a raw array in the display's
+//! colour, so a tile can be copied into the framebuffer one row at a time with
+//! `copy_from_slice`. Textures are stored already scaled for the cache's `Zoom` and
+//! already converted by the display's `Palette`, which is how 1-bit panels get
+//! black-and-white tiles and sprites.
+
+use alloc::vec::Vec;
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use tinybmp::Bmp;
+
+use crate::maze::Maze;
+use crate::render::pixel_format::Palette;
+use crate::render::zoom::Zoom;
+use crate::render::{Sprites, View, visible_tiles};
+use crate::systems::setup::{TextureAssets, TextureId};
+
+/// One decoded texture in row-major order. Sprites have `Option` pixels, `None`
+/// where they are transparent.
+pub struct DecodedTile<T> {
+    pub size: Size,
+    pub pixels: Vec<T>,
+}
+
+impl DecodedTile<Rgb565> {
+    pub fn decode(bmp: &Bmp<'static, Rgb565>) -> Self {
+        let size = bmp.size();
+        let mut pixels = alloc::vec![Rgb565::BLACK; (size.width * size.height) as usize];
+        for Pixel(point, color) in bmp.pixels() {
+            pixels[point.y as usize * size.width as usize + point.x as usize] = color;
+        }
+        Self { size, pixels }
+    }
+
+    /// The tile in the display's colour. The palette sees tile coordinates, so
+    /// dithering scrolls with the maze instead of shimmering over it; tile sizes are
+    /// multiples of 4, so neighbouring tiles continue the pattern.
+    pub fn converted<C: PixelColor>(&self, palette: &impl Palette<C>) -> DecodedTile<C> {
+        self.map(|color, point| palette.convert(color, point))
+    }
+
+    /// Like `converted`, with the pixels of the transparency `key` left out.
+    pub fn sprite<C: PixelColor>(
+        &self,
+        palette: &impl Palette<C>,
+        key: Option<Rgb565>,
+    ) -> DecodedTile<Option<C>> {
+        self.map(|color, point| (Some(color) != key).then(|| palette.convert(color, point)))
+    }
+}
+
+impl<T: Copy> DecodedTile<T> {
+    /// Apply `f` to every pixel and its position in the tile.
+    pub fn map<U>(&self, mut f: impl FnMut(T, Point) -> U) -> DecodedTile<U> {
+        let width = self.size.width as i32;
+        DecodedTile {
+            size: self.size,
+            pixels: self
+                .pixels
+                .iter()
+                .enumerate()
+                .map(|(index, &pixel)| {
+                    f(
+                        pixel,
+                        Point::new(index as i32 % width, index as i32 / width),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Nearest-neighbour resample of the tile to `zoom`.
+    pub fn scaled(self, zoom: Zoom) -> Self {
+        if zoom == Zoom::Normal {
+            return self;
+        }
+        let size = zoom.scale_size(self.size);
+        let mut pixels = Vec::with_capacity((size.width * size.height) as usize);
+        for y in 0..size.height as i32 {
+            for x in 0..size.width as i32 {
+                let (src_x, src_y) = match zoom {
+                    Zoom::Half => (x * 2, y * 2),
+                    _ => (x / 2, y / 2),
+                };
+                pixels.push(self.pixels[(src_y * self.size.width as i32 + src_x) as usize]);
+            }
+        }
+        Self { size, pixels }
+    }
+
+    /// Copy the tile into a row-major pixel buffer of `target_size` with its top-left
+    /// corner at `position`, clipped to the buffer.
+    pub fn blit(&self, target: &mut [T], target_size: Size, position: Point) {
+        let tile_w = self.size.width as i32;
+        let tile_h = self.size.height as i32;
+        let x0 = position.x.max(0);
+        let x1 = (position.x + tile_w).min(target_size.width as i32);
+        let y0 = position.y.max(0);
+        let y1 = (position.y + tile_h).min(target_size.height as i32);
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+        let stride = target_size.width as usize;
+        let len = (x1 - x0) as usize;
+        for y in y0..y1 {
+            let src = ((y - position.y) * tile_w + (x0 - position.x)) as usize;
+            let dst = y as usize * stride + x0 as usize;
+            target[dst..dst + len].copy_from_slice(&self.pixels[src..src + len]);
+        }
+    }
+}
+
+impl<C: PixelColor> DecodedTile<Option<C>> {
+    /// Draw the opaque pixels with the top-left corner at `position`.
+    pub fn draw<D>(&self, target: &mut D, position: Point) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let width = self.size.width as i32;
+        target.draw_iter(self.pixels.iter().enumerate().filter_map(|(index, pixel)| {
+            let point = Point::new(index as i32 % width, index as i32 / width);
+            pixel.map(|color| Pixel(position + point, color))
+        }))
+    }
+}
+
+/// Decoded ground, wall and scorched tiles, indexed by the maze tile value, and the
+/// sprites, in the colour `C` of a display.
+pub struct TileCache<C> {
+    tiles: [Option<DecodedTile<C>>; 3],
+    /// Sprites at the cache's zoom for the scene and at the zooms of the HUD icons.
+    sprites: Vec<(TextureId, Zoom, DecodedTile<Option<C>>)>,
+    zoom: Zoom,
+}
+
+impl<C: PixelColor> TileCache<C> {
+    /// Decode the textures for `zoom` and convert them with the display's `palette`.
+    pub fn new(texture_assets: &TextureAssets, zoom: Zoom, palette: &impl Palette<C>) -> Self {
+        let decode = |bmp: &Option<Bmp<'static, Rgb565>>| {
+            bmp.as_ref()
+                .map(|bmp| DecodedTile::decode(bmp).scaled(zoom).converted(palette))
+        };
+        let mut sprites = Vec::new();
+        for texture in TextureId::ALL {
+            let Some(bmp) = texture_assets.sprite(texture) else {
+                continue;
+            };
+            let zooms = [Zoom::Half, Zoom::Normal, Zoom::Double];
+            for sprite_zoom in zooms
+                .into_iter()
+                .filter(|&z| z != Zoom::Double || z == zoom)
+            {
+                let sprite = DecodedTile::decode(bmp)
+                    .scaled(sprite_zoom)
+                    .sprite(palette, texture_assets.transparency_key);
+                sprites.push((texture, sprite_zoom, sprite));
+            }
+        }
+        Self {
+            zoom,
+            sprites,
+            tiles: [
+                decode(&texture_assets.ground),
+                decode(&texture_assets.wall),
+                decode(&texture_assets.scorched),
+            ],
+        }
+    }
+
+    /// The zoom the tiles were scaled for.
+    pub fn zoom(&self) -> Zoom {
+        self.zoom
+    }
+
+    /// The decoded texture for a maze tile value; unknown values use the ground tile.
+    pub fn tile(&self, tile: u8) -> Option<&DecodedTile<C>> {
+        self.tiles
+            .get(tile as usize)
+            .unwrap_or(&self.tiles[0])
+            .as_ref()
+    }
+
+    /// The decoded sprite scaled by `zoom`; `Double` is only decoded when it is the
+    /// cache's zoom.
+    pub fn sprite(&self, texture: TextureId, zoom: Zoom) -> Option<&DecodedTile<Option<C>>> {
+        self.sprites
+            .iter()
+            .find(|(id, sprite_zoom, _)| *id == texture && *sprite_zoom == zoom)
+            .map(|(_, _, sprite)| sprite)
+    }
+
+    /// Draw the tiles that intersect the viewport into a row-major pixel buffer.
+    pub fn draw_maze(&self, pixels: &mut [C], view: &View, maze: &Maze) {
+        for (tile, screen) in visible_tiles(maze, view) {
+            if let Some(decoded) = self.tile(tile) {
+                decoded.blit(pixels, view.viewport, screen);
+            }
+        }
+    }
+}
+
+impl<C: PixelColor> Sprites<C> for TileCache<C> {
+    fn size(&self, texture: TextureId) -> Option<Size> {
+        self.sprite(texture, Zoom::Normal).map(|sprite| sprite.size)
+    }
+
+    fn draw<D>(
+        &self,
+        target: &mut D,
+        texture: TextureId,
+        screen: Point,
+        zoom: Zoom,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        match self.sprite(texture, zoom) {
+            Some(sprite) => sprite.draw(target, screen),
+            None => Ok(()),
+        }
+    }
+}
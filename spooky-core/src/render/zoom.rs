@@ -0,0 +1,119 @@
+//! Render-time zoom.
+//!
+//! Zoom only changes how the world is mapped to the screen; gameplay coordinates
+//! (and `Maze::tile_width`/`tile_height`) stay the same. At `Half` a 16x16 tile
+//! becomes 8x8, which shows four times as much of the maze on tiny panels.
+
+use bevy::prelude::Resource;
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// Scale of the world on screen.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Zoom {
+    /// 0.5x, every other pixel of the textures.
+    Half,
+    /// 1x.
+    #[default]
+    Normal,
+    /// 2x, every texture pixel becomes a 2x2 block.
+    Double,
+}
+
+impl Zoom {
+    /// Scale a length or a coordinate difference from world to screen pixels.
+    pub fn scale(self, length: i32) -> i32 {
+        match self {
+            Zoom::Half => length.div_euclid(2),
+            Zoom::Normal => length,
+            Zoom::Double => length * 2,
+        }
+    }
+
+    pub fn scale_size(self, size: Size) -> Size {
+        Size::new(
+            self.scale(size.width as i32) as u32,
+            self.scale(size.height as i32) as u32,
+        )
+    }
+
+    /// Screen position of a world position, with `camera` in the centre of the viewport.
+    pub fn to_screen(self, world: Point, camera: Point, viewport: Size) -> Point {
+        let delta = world - camera;
+        Point::new(self.scale(delta.x), self.scale(delta.y))
+            + Point::new(viewport.width as i32 / 2, viewport.height as i32 / 2)
+    }
+
+    /// World position shown at a screen position; the inverse of `to_screen`.
+    pub fn to_world(self, screen: Point, camera: Point, viewport: Size) -> Point {
+        let delta = screen - Point::new(viewport.width as i32 / 2, viewport.height as i32 / 2);
+        let unscale = |value: i32| match self {
+            Zoom::Half => value * 2,
+            Zoom::Normal => value,
+            Zoom::Double => value.div_euclid(2),
+        };
+        camera + Point::new(unscale(delta.x), unscale(delta.y))
+    }
+}
+
+/// Draw target adapter that scales whatever is drawn into it around `origin`:
+/// a pixel at `p` lands at `origin + p * zoom` of the wrapped target.
+/// Draw images at `Point::zero()` and put their screen position in `origin`.
+pub struct Zoomed<'a, D: DrawTarget<Color = Rgb565>> {
+    pub target: &'a mut D,
+    pub zoom: Zoom,
+    pub origin: Point,
+}
+
+impl<'a, D: DrawTarget<Color = Rgb565>> Dimensions for Zoomed<'a, D> {
+    fn bounding_box(&self) -> Rectangle {
+        let area = self.target.bounding_box();
+        let unscale = |value: i32| match self.zoom {
+            Zoom::Half => value * 2,
+            Zoom::Normal => value,
+            Zoom::Double => value.div_euclid(2),
+        };
+        let top_left = area.top_left - self.origin;
+        Rectangle::new(
+            Point::new(unscale(top_left.x), unscale(top_left.y)),
+            Size::new(
+                unscale(area.size.width as i32) as u32,
+                unscale(area.size.height as i32) as u32,
+            ),
+        )
+    }
+}
+
+impl<'a, D: DrawTarget<Color = Rgb565>> DrawTarget for Zoomed<'a, D> {
+    type Color = Rgb565;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let origin = self.origin;
+        match self.zoom {
+            Zoom::Half => self.target.draw_iter(
+                pixels
+                    .into_iter()
+                    .filter(|Pixel(point, _)| point.x % 2 == 0 && point.y % 2 == 0)
+                    .map(|Pixel(point, color)| Pixel(origin + point / 2, color)),
+            ),
+            Zoom::Normal => self.target.draw_iter(
+                pixels
+                    .into_iter()
+                    .map(|Pixel(point, color)| Pixel(origin + point, color)),
+            ),
+            Zoom::Double => {
+                self.target
+                    .draw_iter(pixels.into_iter().flat_map(|Pixel(point, color)| {
+                        let corner = origin + point * 2;
+                        [(0, 0), (1, 0), (0, 1), (1, 1)]
+                            .map(|(dx, dy)| Pixel(corner + Point::new(dx, dy), color))
+                    }))
+            }
+        }
+    }
+}
@@ -0,0 +1,573 @@
+//! Deterministic input recording and replay.
+//!
+//! A `Recording` holds everything a game depends on: the maze seed and config, the
+//! movement mode, the length of a tick and the input messages of every tick. The
+//! maze's random numbers come from the seed and the game logic only runs in fixed
+//! ticks, so feeding the same input into the same ticks gives the same game. A
+//! `Recorder` captures a game and a `Replay` feeds it back through the normal input
+//! messages; both compare `state_hash` after the last tick.
+//!
+//! `Recording::encode` writes a compact binary format that fits in flash or a serial
+//! log: numbers are LEB128 varints and runs of identical ticks, such as the long
+//! stretches without input, are stored once with a repeat count.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use bevy::prelude::*;
+use log::{info, warn};
+
+use crate::events::action::{PlayerAction, PlayerActionMessage};
+use crate::events::player::{PlayerAnalogInputMessage, PlayerInputMessage};
+use crate::maze::{Maze, MazeAlgorithm};
+use crate::movement::MovementMode;
+use crate::resources::{MazeConfig, MazeResource, MazeSeed, PlayerPosition};
+use crate::seed_code::SeedCode;
+use crate::systems::TickSet;
+use crate::systems::hud::HudState;
+
+/// First bytes of an encoded recording.
+pub const MAGIC: [u8; 4] = *b"SPKR";
+/// Version of the encoding written by `Recording::encode`. Version 2 games draw
+/// their random numbers from the `GameRng` streams.
+const VERSION: u8 = 2;
+
+/// The input messages of one tick.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TickInput {
+    /// `PlayerInputMessage` steps in whole pixels, in the order they were sent.
+    pub moves: Vec<(i32, i32)>,
+    /// The `PlayerAnalogInputMessage` the game used; `None` for no or zero input.
+    pub analog: Option<(f32, f32)>,
+    pub actions: Vec<PlayerAction>,
+}
+
+/// A recorded game, see the module documentation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    pub seed: [u8; 32],
+    pub maze: MazeConfig,
+    pub movement_mode: MovementMode,
+    /// Duration of one game tick, `Time<Fixed>`'s timestep.
+    pub game_step: Duration,
+    pub ticks: Vec<TickInput>,
+    /// `state_hash` after the last tick; a replay that ends elsewhere has diverged.
+    pub final_hash: Option<u64>,
+}
+
+impl Recording {
+    /// An empty recording of a game with these settings.
+    pub fn new(
+        seed: [u8; 32],
+        maze: MazeConfig,
+        movement_mode: MovementMode,
+        game_step: Duration,
+    ) -> Self {
+        Self {
+            seed,
+            maze,
+            movement_mode,
+            game_step,
+            ticks: Vec::new(),
+            final_hash: None,
+        }
+    }
+
+    /// The binary form of the recording, read back by `decode`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&MAGIC);
+        out.push(VERSION);
+
+        // Seeds made from a code take a few bytes instead of 32.
+        match SeedCode::from_seed(&self.seed) {
+            Some(code) => {
+                out.push(0);
+                write_varint(&mut out, code.0);
+            }
+            None => {
+                out.push(1);
+                out.extend_from_slice(&self.seed);
+            }
+        }
+
+        write_varint(&mut out, self.maze.width.into());
+        write_varint(&mut out, self.maze.height.into());
+        out.push(match self.maze.algorithm {
+            MazeAlgorithm::Backtracking => 0,
+            MazeAlgorithm::Prim => 1,
+        });
+        out.push(self.maze.static_layout.into());
+        write_varint(&mut out, self.maze.coins.into());
+        write_varint(&mut out, self.maze.npcs.into());
+
+        match self.movement_mode {
+            MovementMode::Grid => out.push(0),
+            MovementMode::Continuous { max_speed } => {
+                out.push(1);
+                write_varint(&mut out, max_speed.to_bits().into());
+            }
+        }
+        write_varint(&mut out, self.game_step.as_nanos() as u64);
+
+        match self.final_hash {
+            Some(hash) => {
+                out.push(1);
+                out.extend_from_slice(&hash.to_le_bytes());
+            }
+            None => out.push(0),
+        }
+
+        // Runs of identical ticks until the end of the data.
+        let mut ticks = self.ticks.iter().peekable();
+        while let Some(tick) = ticks.next() {
+            let mut run = 1u64;
+            while ticks.next_if(|next| *next == tick).is_some() {
+                run += 1;
+            }
+            write_varint(&mut out, run);
+            write_tick(&mut out, tick);
+        }
+        out
+    }
+
+    /// Read a recording written by `encode`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(ReplayError::NotARecording);
+        }
+        let version = reader.byte()?;
+        if version != VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let seed = match reader.byte()? {
+            0 => SeedCode(reader.varint()?).to_seed(),
+            1 => {
+                let mut seed = [0u8; 32];
+                seed.copy_from_slice(reader.take(32)?);
+                seed
+            }
+            _ => return Err(ReplayError::InvalidValue("seed")),
+        };
+
+        let maze = MazeConfig {
+            width: reader.u32("maze width")?,
+            height: reader.u32("maze height")?,
+            algorithm: match reader.byte()? {
+                0 => MazeAlgorithm::Backtracking,
+                1 => MazeAlgorithm::Prim,
+                _ => return Err(ReplayError::InvalidValue("maze algorithm")),
+            },
+            static_layout: reader.flag("static layout")?,
+            coins: reader.u32("coins")?,
+            npcs: reader.u32("npcs")?,
+        };
+
+        let movement_mode = match reader.byte()? {
+            0 => MovementMode::Grid,
+            1 => MovementMode::Continuous {
+                max_speed: f32::from_bits(reader.u32("max speed")?),
+            },
+            _ => return Err(ReplayError::InvalidValue("movement mode")),
+        };
+        let game_step = Duration::from_nanos(reader.varint()?);
+
+        let final_hash = if reader.flag("final hash")? {
+            let mut hash = [0u8; 8];
+            hash.copy_from_slice(reader.take(8)?);
+            Some(u64::from_le_bytes(hash))
+        } else {
+            None
+        };
+
+        let mut ticks = Vec::new();
+        while !reader.bytes.is_empty() {
+            let run = reader.varint()?;
+            if run == 0 {
+                return Err(ReplayError::InvalidValue("tick run"));
+            }
+            let tick = reader.tick()?;
+            for _ in 1..run {
+                ticks.push(tick.clone());
+            }
+            ticks.push(tick);
+        }
+
+        Ok(Self {
+            seed,
+            maze,
+            movement_mode,
+            game_step,
+            ticks,
+            final_hash,
+        })
+    }
+}
+
+/// Errors reported while decoding a recording.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The data does not start with `MAGIC`.
+    NotARecording,
+    UnsupportedVersion(u8),
+    /// The data ends in the middle of a value.
+    Truncated,
+    /// A field holds a value no recorder writes.
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::NotARecording => write!(f, "not a spooky maze recording"),
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "unsupported recording version {}", version)
+            }
+            ReplayError::Truncated => write!(f, "recording is truncated"),
+            ReplayError::InvalidValue(field) => write!(f, "invalid {} in recording", field),
+        }
+    }
+}
+
+/// FNV-1a hash of the game state the logic works on: the maze with its items and
+/// NPCs, the player's position and the HUD counters. Sprites and the camera follow
+/// from these and are left out.
+pub fn state_hash(maze: &Maze, player: &PlayerPosition, hud: &HudState) -> u64 {
+    let mut hash = Fnv::default();
+    hash.write(&player.x.to_bits().to_le_bytes());
+    hash.write(&player.y.to_bits().to_le_bytes());
+    hash.write(&maze.data);
+    for item in maze
+        .coins
+        .iter()
+        .chain(&maze.walkers)
+        .chain(&maze.dynamites)
+    {
+        hash.write_i32(item.x);
+        hash.write_i32(item.y);
+    }
+    for npc in &maze.npcs {
+        for value in [
+            npc.x,
+            npc.y,
+            npc.vector_x,
+            npc.vector_y,
+            npc.steps_remaining,
+        ] {
+            hash.write_i32(value);
+        }
+    }
+    for value in [
+        maze.coin_counter,
+        hud.coins_left,
+        hud.teleport_countdown,
+        hud.walker_timer,
+        hud.dynamites,
+        hud.level,
+    ] {
+        hash.write(&value.to_le_bytes());
+    }
+    hash.0
+}
+
+/// Captures the input of every tick into `recording`. Added by `add_recorder`.
+#[derive(Resource)]
+pub struct Recorder {
+    pub recording: Recording,
+}
+
+/// Record a game: the settings in `recording` are used for the game and every tick's
+/// input is appended to it. Take the `Recorder` resource out of the app to save it.
+pub fn add_recorder(app: &mut App, recording: Recording) {
+    insert_settings(app, &recording);
+    app.insert_resource(Recorder { recording }).add_systems(
+        FixedUpdate,
+        (
+            record_input.in_set(TickSet::Record),
+            record_state.after(TickSet::Logic),
+        ),
+    );
+}
+
+/// Appends the input messages of this tick, as the game systems will see them.
+pub fn record_input(
+    mut recorder: ResMut<Recorder>,
+    mut moves: MessageReader<PlayerInputMessage>,
+    mut analog: MessageReader<PlayerAnalogInputMessage>,
+    mut actions: MessageReader<PlayerActionMessage>,
+) {
+    // `apply_continuous_movement` only uses the strongest analog input of a tick.
+    let analog = analog
+        .read()
+        .map(|input| (input.x, input.y))
+        .max_by(|a, b| {
+            let length =
+                |(x, y): (f32, f32)| Vec2::new(x, y).clamp_length_max(1.0).length_squared();
+            length(*a).total_cmp(&length(*b))
+        })
+        .filter(|&(x, y)| x != 0.0 || y != 0.0);
+    let tick = TickInput {
+        moves: moves
+            .read()
+            .map(|input| (input.dx as i32, input.dy as i32))
+            .collect(),
+        analog,
+        actions: actions.read().map(|input| input.action).collect(),
+    };
+    recorder.recording.ticks.push(tick);
+}
+
+/// Keeps `final_hash` up to date with the state after the latest tick.
+pub fn record_state(
+    mut recorder: ResMut<Recorder>,
+    maze_res: Res<MazeResource>,
+    player_pos: Res<PlayerPosition>,
+    hud_state: Res<HudState>,
+) {
+    recorder.recording.final_hash = Some(state_hash(&maze_res.maze, &player_pos, &hud_state));
+}
+
+/// Feeds a recording back into the game. Added by `add_replay`.
+#[derive(Resource)]
+pub struct Replay {
+    recording: Recording,
+    next: usize,
+    final_hash: Option<u64>,
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            next: 0,
+            final_hash: None,
+        }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Whether every recorded tick has been played.
+    pub fn is_finished(&self) -> bool {
+        self.final_hash.is_some()
+    }
+
+    /// `state_hash` after the last recorded tick, once the replay is finished.
+    pub fn final_hash(&self) -> Option<u64> {
+        self.final_hash
+    }
+
+    /// Whether the replay ended in the recorded state; `None` while it runs or when
+    /// the recording has no hash.
+    pub fn matches(&self) -> Option<bool> {
+        Some(self.final_hash? == self.recording.final_hash?)
+    }
+}
+
+/// Play a recording with its own seed, maze and timing. The front-end leaves out its
+/// live input systems and keeps rendering as usual; game time pauses after the last
+/// tick.
+pub fn add_replay(app: &mut App, recording: Recording) {
+    insert_settings(app, &recording);
+    app.insert_resource(Replay::new(recording)).add_systems(
+        FixedUpdate,
+        (
+            replay_input.in_set(TickSet::Input),
+            check_replay.after(TickSet::Logic),
+        ),
+    );
+}
+
+/// Writes the recorded input messages of the next tick.
+pub fn replay_input(
+    mut replay: ResMut<Replay>,
+    mut input_writer: MessageWriter<PlayerInputMessage>,
+    mut analog_writer: MessageWriter<PlayerAnalogInputMessage>,
+    mut action_writer: MessageWriter<PlayerActionMessage>,
+) {
+    let Some(tick) = replay.recording.ticks.get(replay.next) else {
+        return;
+    };
+    for &(dx, dy) in &tick.moves {
+        input_writer.write(PlayerInputMessage {
+            dx: dx as f32,
+            dy: dy as f32,
+        });
+    }
+    if let Some((x, y)) = tick.analog {
+        analog_writer.write(PlayerAnalogInputMessage { x, y });
+    }
+    for &action in &tick.actions {
+        action_writer.write(PlayerActionMessage { action });
+    }
+    replay.next += 1;
+}
+
+/// After the last recorded tick: hashes the state, compares it with the recording and
+/// pauses the game so that it can be inspected.
+pub fn check_replay(
+    mut replay: ResMut<Replay>,
+    maze_res: Res<MazeResource>,
+    player_pos: Res<PlayerPosition>,
+    hud_state: Res<HudState>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if replay.is_finished() || replay.next < replay.recording.ticks.len() {
+        return;
+    }
+    let hash = state_hash(&maze_res.maze, &player_pos, &hud_state);
+    replay.final_hash = Some(hash);
+    time.pause();
+    match replay.matches() {
+        Some(false) => warn!(
+            "Replay diverged: state hash {:016x}, recorded {:016x}",
+            hash,
+            replay.recording.final_hash.unwrap_or_default()
+        ),
+        _ => info!(
+            "Replay finished after {} ticks, state hash {:016x}",
+            replay.recording.ticks.len(),
+            hash
+        ),
+    }
+}
+
+/// The game settings of a recording as resources.
+fn insert_settings(app: &mut App, recording: &Recording) {
+    app.insert_resource(MazeSeed(Some(recording.seed)))
+        .insert_resource(recording.maze)
+        .insert_resource(recording.movement_mode)
+        .insert_resource(Time::<Fixed>::from_duration(recording.game_step));
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Zigzag encoding keeps small negative steps as short as positive ones.
+fn write_signed(out: &mut Vec<u8>, value: i32) {
+    write_varint(out, ((value << 1) ^ (value >> 31)) as u32 as u64);
+}
+
+fn write_tick(out: &mut Vec<u8>, tick: &TickInput) {
+    write_varint(out, tick.moves.len() as u64);
+    for &(dx, dy) in &tick.moves {
+        write_signed(out, dx);
+        write_signed(out, dy);
+    }
+    match tick.analog {
+        Some((x, y)) => {
+            out.push(1);
+            write_varint(out, x.to_bits().into());
+            write_varint(out, y.to_bits().into());
+        }
+        None => out.push(0),
+    }
+    write_varint(out, tick.actions.len() as u64);
+    for action in &tick.actions {
+        out.push(match action {
+            PlayerAction::Teleport => 0,
+            PlayerAction::PlaceDynamite => 1,
+        });
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], ReplayError> {
+        if self.bytes.len() < count {
+            return Err(ReplayError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, ReplayError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn flag(&mut self, field: &'static str) -> Result<bool, ReplayError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ReplayError::InvalidValue(field)),
+        }
+    }
+
+    fn varint(&mut self) -> Result<u64, ReplayError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ReplayError::InvalidValue("varint"))
+    }
+
+    fn u32(&mut self, field: &'static str) -> Result<u32, ReplayError> {
+        u32::try_from(self.varint()?).map_err(|_| ReplayError::InvalidValue(field))
+    }
+
+    fn signed(&mut self, field: &'static str) -> Result<i32, ReplayError> {
+        let value = self.u32(field)?;
+        Ok((value >> 1) as i32 ^ -((value & 1) as i32))
+    }
+
+    fn tick(&mut self) -> Result<TickInput, ReplayError> {
+        let mut tick = TickInput::default();
+        for _ in 0..self.varint()? {
+            tick.moves
+                .push((self.signed("move")?, self.signed("move")?));
+        }
+        if self.flag("analog input")? {
+            tick.analog = Some((
+                f32::from_bits(self.u32("analog input")?),
+                f32::from_bits(self.u32("analog input")?),
+            ));
+        }
+        for _ in 0..self.varint()? {
+            tick.actions.push(match self.byte()? {
+                0 => PlayerAction::Teleport,
+                1 => PlayerAction::PlaceDynamite,
+                _ => return Err(ReplayError::InvalidValue("action")),
+            });
+        }
+        Ok(tick)
+    }
+}
+
+/// 64-bit FNV-1a, small and the same on every target.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write(&value.to_le_bytes());
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::maze::Maze;
+use crate::maze::{Maze, MazeAlgorithm};
 
 /// Wraps the maze so that it can be used as a Bevy resource.
 #[derive(Resource)]
@@ -18,6 +18,47 @@ impl Default for MazeSeed {
     }
 }
 
+/// Shape and contents of the maze `setup` builds.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MazeConfig {
+    /// Fields per row and column of a generated maze; each field is a floor tile with
+    /// a wall or passage next to it. Only used with the `dynamic_maze` feature.
+    pub width: u32,
+    pub height: u32,
+    pub algorithm: MazeAlgorithm,
+    /// Play the fixed maze of the `static_maze` feature instead of generating one.
+    pub static_layout: bool,
+    /// Coins and NPCs placed at the start, at most `Maze::MAX_COINS` and
+    /// `Maze::MAX_NPCS`.
+    pub coins: u32,
+    pub npcs: u32,
+}
+
+impl MazeConfig {
+    /// The player starts on tile (11, 10), which needs 6 fields.
+    pub const MIN_SIZE: u32 = 6;
+    /// The 64x64 tiles of `Maze::data` hold 31 fields.
+    pub const MAX_SIZE: u32 = 31;
+
+    /// Tiles a maze of `fields` fields spans, walls included.
+    pub const fn tiles(fields: u32) -> u32 {
+        (fields + 1) * 2
+    }
+}
+
+impl Default for MazeConfig {
+    fn default() -> Self {
+        Self {
+            width: Self::MAX_SIZE,
+            height: Self::MAX_SIZE,
+            algorithm: MazeAlgorithm::default(),
+            static_layout: cfg!(feature = "static_maze"),
+            coins: Maze::MAX_COINS,
+            npcs: Maze::MAX_NPCS as u32,
+        }
+    }
+}
+
 #[derive(Resource, Debug)]
 pub struct PlayerPosition {
     pub x: f32,
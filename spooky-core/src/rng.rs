@@ -0,0 +1,112 @@
+//! The game's random numbers.
+//!
+//! `GameRng` splits the randomness of a game into independent streams derived from
+//! the `MazeSeed`, one per kind of use. A feature that draws from one stream leaves
+//! the others untouched, so a new effect does not move every NPC and coin of an
+//! existing seed or recording. New kinds of randomness get a new `Stream`.
+
+use bevy::prelude::Resource;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+/// Seed used when the `MazeSeed` has none.
+pub const DEFAULT_SEED: [u8; 32] = [42; 32];
+
+/// The independent random streams of a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stream {
+    /// Carving the maze.
+    Layout,
+    /// Placing and relocating coins, walkers and dynamite.
+    Items,
+    /// NPC start positions and directions.
+    Npc,
+    /// Where teleports and NPC catches put the player.
+    Effects,
+}
+
+impl Stream {
+    pub const ALL: [Stream; 4] = [Stream::Layout, Stream::Items, Stream::Npc, Stream::Effects];
+
+    /// ChaCha stream number. `Layout` is stream 0, the plain generator of the seed,
+    /// so mazes carved before the streams were split keep their layout.
+    fn id(self) -> u64 {
+        match self {
+            Stream::Layout => 0,
+            Stream::Items => 1,
+            Stream::Npc => 2,
+            Stream::Effects => 3,
+        }
+    }
+}
+
+/// One random stream with range helpers that do not depend on the `rand` version.
+#[derive(Clone, Debug)]
+pub struct StreamRng(ChaChaRng);
+
+impl StreamRng {
+    /// A stream of its own, for generators that are seeded with bytes from a
+    /// `GameRng` stream.
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self(ChaChaRng::from_seed(seed))
+    }
+
+    /// A number in `0..n`, every value equally likely. `n` must not be 0.
+    pub fn below(&mut self, n: u32) -> u32 {
+        assert!(n > 0, "empty random range");
+        // Lemire's multiply-and-reject: the low half of the product decides whether
+        // the draw falls into the part of the range that would favour small values.
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let product = u64::from(self.0.next_u32()) * u64::from(n);
+            if product as u32 >= threshold {
+                return (product >> 32) as u32;
+            }
+        }
+    }
+
+    /// A number in `start..end`, every value equally likely. The range must not be
+    /// empty.
+    pub fn range(&mut self, range: core::ops::Range<i32>) -> i32 {
+        assert!(range.start < range.end, "empty random range");
+        let span = range.end.wrapping_sub(range.start) as u32;
+        range.start.wrapping_add(self.below(span) as i32)
+    }
+
+    /// One of `items`, each equally likely. `items` must not be empty.
+    pub fn choose<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len() as u32) as usize]
+    }
+
+    /// 32 random bytes, e.g. to seed a maze generator.
+    pub fn seed(&mut self) -> [u8; 32] {
+        self.0.r#gen()
+    }
+}
+
+/// The random streams of a game, inserted by `setup` from the `MazeSeed`.
+#[derive(Resource, Clone, Debug)]
+pub struct GameRng {
+    streams: [StreamRng; 4],
+}
+
+impl GameRng {
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self {
+            streams: Stream::ALL.map(|stream| {
+                let mut rng = ChaChaRng::from_seed(seed);
+                rng.set_stream(stream.id());
+                StreamRng(rng)
+            }),
+        }
+    }
+
+    /// The streams of a `MazeSeed`, `DEFAULT_SEED` for none.
+    pub fn from_maze_seed(seed: Option<[u8; 32]>) -> Self {
+        Self::new(seed.unwrap_or(DEFAULT_SEED))
+    }
+
+    pub fn stream(&mut self, stream: Stream) -> &mut StreamRng {
+        &mut self.streams[stream.id() as usize]
+    }
+}
@@ -0,0 +1,134 @@
+//! Maze seeds that people can read, type and share.
+//!
+//! A `SeedCode` is a 64-bit number written in Crockford's base 32. Random codes
+//! have 40 bits, which are 8 digits such as `7KQ2-M9XD`. Every front-end expands a
+//! code to the 32-byte `MazeSeed` the same way, so one code gives the same maze on
+//! the desktop, in the browser, in the terminal and on the boards.
+
+use core::fmt;
+use core::str::FromStr;
+
+/// Crockford's base 32 alphabet: no I, L, O or U.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// Digits of a 64-bit code.
+const DIGITS: usize = 13;
+/// Digits shown at least, the length of a random code.
+const MIN_DIGITS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SeedCode(pub u64);
+
+impl SeedCode {
+    /// Bits of a random code.
+    pub const RANDOM_BITS: u32 = 40;
+
+    /// A code of at most 8 digits from a random number.
+    pub fn random(random: u64) -> Self {
+        Self(random & ((1 << Self::RANDOM_BITS) - 1))
+    }
+
+    /// The code of a seed that came from one, otherwise a random code made from the
+    /// seed's bytes. Turns the entropy of a hardware or system random source into a
+    /// seed that can be shown and typed in again.
+    pub fn from_entropy(entropy: &[u8; 32]) -> Self {
+        Self::from_seed(entropy).unwrap_or_else(|| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&entropy[..8]);
+            Self::random(u64::from_le_bytes(bytes))
+        })
+    }
+
+    /// The `MazeSeed` of this code: the little-endian bytes of the number, four times.
+    pub fn to_seed(self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        for chunk in seed.chunks_mut(8) {
+            chunk.copy_from_slice(&self.0.to_le_bytes());
+        }
+        seed
+    }
+
+    /// The code a seed was expanded from, or `None` for seeds that did not come from
+    /// a code, such as 32 random bytes.
+    pub fn from_seed(seed: &[u8; 32]) -> Option<Self> {
+        let (first, rest) = seed.split_at(8);
+        if rest.chunks(8).any(|chunk| chunk != first) {
+            return None;
+        }
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(first);
+        Some(Self(u64::from_le_bytes(bytes)))
+    }
+}
+
+impl fmt::Display for SeedCode {
+    /// At least 8 digits, in groups of 4 from the right: `0000-00S0`, `7KQ2-M9XD`,
+    /// `F-ZZZZ-ZZZZ-ZZZZ`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = [0u8; DIGITS];
+        let mut value = self.0;
+        for digit in digits.iter_mut().rev() {
+            *digit = ALPHABET[(value & 31) as usize];
+            value >>= 5;
+        }
+        let leading_zeros = digits.iter().take_while(|&&digit| digit == b'0').count();
+        let length = (DIGITS - leading_zeros).max(MIN_DIGITS);
+        for (index, digit) in digits[DIGITS - length..].iter().enumerate() {
+            if index > 0 && (length - index) % 4 == 0 {
+                f.write_str("-")?;
+            }
+            write!(f, "{}", *digit as char)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors reported while parsing a seed code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedCodeError {
+    Empty,
+    InvalidDigit(char),
+    TooLarge,
+}
+
+impl fmt::Display for SeedCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedCodeError::Empty => write!(f, "empty seed code"),
+            SeedCodeError::InvalidDigit(digit) => {
+                write!(f, "`{}` is not a seed code digit", digit)
+            }
+            SeedCodeError::TooLarge => write!(f, "seed code has more than 64 bits"),
+        }
+    }
+}
+
+impl FromStr for SeedCode {
+    type Err = SeedCodeError;
+
+    /// Case does not matter and dashes and spaces are skipped. As usual for Crockford's
+    /// base 32, O reads as 0 and I and L as 1. Leading zeros may be left out: `SPOOKY`
+    /// is the same as `00SP-00KY`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut value: u64 = 0;
+        let mut empty = true;
+        for c in text.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+            let digit = match c.to_ascii_uppercase() {
+                'O' => 0,
+                'I' | 'L' => 1,
+                upper => ALPHABET
+                    .iter()
+                    .position(|&a| a as char == upper)
+                    .ok_or(SeedCodeError::InvalidDigit(c))? as u64,
+            };
+            if value >> 59 != 0 {
+                return Err(SeedCodeError::TooLarge);
+            }
+            value = value << 5 | digit;
+            empty = false;
+        }
+        if empty {
+            return Err(SeedCodeError::Empty);
+        }
+        Ok(Self(value))
+    }
+}
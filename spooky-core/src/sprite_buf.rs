@@ -1,58 +1,72 @@
 use embedded_graphics::pixelcolor::Rgb565;
-use embedded_graphics::prelude::RgbColor;
 use embedded_graphics::{
     geometry::{OriginDimensions, Size},
-    prelude::{DrawTarget, Pixel},
+    prelude::{DrawTarget, Pixel, PixelColor},
 };
 use embedded_graphics_framebuf::FrameBuf;
 use embedded_graphics_framebuf::backends::FrameBufferBackend;
 
-pub struct SpriteBuf<'a, B: FrameBufferBackend<Color = Rgb565>> {
-    pub fbuf: &'a mut FrameBuf<Rgb565, B>,
+/// "Magic pink", the transparency key of the bundled sprites.
+pub const MAGIC_PINK: Rgb565 = Rgb565::new(31, 0, 31);
+
+/// A borrowed draw target wrapper that skips pixels of the transparency key.
+/// Sprites drawn through it keep the background wherever the bitmap is transparent.
+pub struct SpriteBuf<'a, D: DrawTarget> {
+    pub target: &'a mut D,
+    /// Colour that is not drawn; `None` draws every pixel.
+    pub key: Option<D::Color>,
+}
+
+impl<'a, D: DrawTarget<Color = Rgb565>> SpriteBuf<'a, D> {
+    /// Wrap an `Rgb565` target with "magic pink" as the transparency key.
+    pub fn new(target: &'a mut D) -> Self {
+        Self::with_key(target, Some(MAGIC_PINK))
+    }
+}
+
+impl<'a, D: DrawTarget> SpriteBuf<'a, D> {
+    pub fn with_key(target: &'a mut D, key: Option<D::Color>) -> Self {
+        Self { target, key }
+    }
 }
 
-impl<'a, B> OriginDimensions for SpriteBuf<'a, B>
+impl<'a, D> OriginDimensions for SpriteBuf<'a, D>
 where
-    B: embedded_graphics_framebuf::backends::FrameBufferBackend<Color = Rgb565>,
+    D: DrawTarget,
 {
     fn size(&self) -> Size {
-        self.fbuf.size()
+        self.target.bounding_box().size
     }
 }
 
-impl<'a, B> DrawTarget for SpriteBuf<'a, B>
+impl<'a, D> DrawTarget for SpriteBuf<'a, D>
 where
-    B: FrameBufferBackend<Color = Rgb565>,
+    D: DrawTarget,
 {
-    type Color = Rgb565;
-    type Error = core::convert::Infallible;
+    type Color = D::Color;
+    type Error = D::Error;
 
     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        for Pixel(coord, color) in pixels.into_iter() {
-            // Filter out "magic pink" (RGB565: R==31, G==0, B==31)
-            if color.r() == 31 && color.g() == 0 && color.b() == 31 {
-                continue;
-            }
-            if coord.x >= 0
-                && coord.x < self.fbuf.width() as i32
-                && coord.y >= 0
-                && coord.y < self.fbuf.height() as i32
-            {
-                self.fbuf.set_color_at(coord, color);
-            }
-        }
-        Ok(())
+        // Skip the key colour; the wrapped target clips the remaining pixels to its
+        // own bounds.
+        let key = self.key;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .filter(|Pixel(_, color)| Some(*color) != key),
+        )
     }
 }
 
-impl<'a, B> SpriteBuf<'a, B>
+impl<'a, C, B> SpriteBuf<'a, FrameBuf<C, B>>
 where
-    B: FrameBufferBackend<Color = Rgb565>,
+    C: PixelColor,
+    B: FrameBufferBackend<Color = C>,
 {
-    pub fn get_pixel_iter(&self) -> impl Iterator<Item = Rgb565> + '_ {
-        self.fbuf.into_iter().map(|pixel| pixel.1)
+    pub fn get_pixel_iter(&self) -> impl Iterator<Item = C> + '_ {
+        self.target.into_iter().map(|pixel| pixel.1)
     }
 }
@@ -1,6 +1,7 @@
 use crate::components::CoinComponent;
 use crate::events::coin::CoinCollisionMessage;
 use crate::maze::Coin;
+use crate::movement::{MovementMode, touches_item};
 use crate::resources::{MazeResource, PlayerPosition};
 use crate::systems::hud::HudState;
 use bevy::prelude::*;
@@ -10,14 +11,20 @@ use bevy::prelude::*;
 pub fn detect_coin_collision(
     player_pos: Res<PlayerPosition>,
     maze_res: Res<MazeResource>,
+    movement_mode: Option<Res<MovementMode>>,
     mut event_writer: MessageWriter<CoinCollisionMessage>,
 ) {
-    // Assuming the player moves in tile increments, cast the logical position to i32.
-    let player_tile_x = player_pos.x as i32;
-    let player_tile_y = player_pos.y as i32;
+    let mode = movement_mode.map(|mode| *mode).unwrap_or_default();
 
     for coin in maze_res.maze.coins.iter() {
-        if coin.x == player_tile_x && coin.y == player_tile_y {
+        if touches_item(
+            mode,
+            &maze_res.maze,
+            player_pos.x,
+            player_pos.y,
+            coin.x,
+            coin.y,
+        ) {
             event_writer.write(CoinCollisionMessage {
                 coin_x: coin.x,
                 coin_y: coin.y,
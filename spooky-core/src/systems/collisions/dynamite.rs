@@ -1,7 +1,10 @@
 use crate::components::DynamiteComponent;
 use crate::events::dynamite::DynamiteCollisionMessage;
 use crate::maze::Coin;
+use crate::movement::{MovementMode, touches_item};
 use crate::resources::{MazeResource, PlayerPosition};
+use crate::rng::{GameRng, Stream};
+use crate::systems::hud::HudState;
 use bevy::prelude::*;
 
 /// This system checks the player's current tile against the dynamite tile(s)
@@ -10,15 +13,21 @@ use bevy::prelude::*;
 pub fn detect_dynamite_collision(
     player_pos: Res<PlayerPosition>,
     maze_res: Res<MazeResource>,
+    movement_mode: Option<Res<MovementMode>>,
     mut event_writer: MessageWriter<DynamiteCollisionMessage>,
 ) {
-    // We assume the player moves in tile increments.
-    let player_tile_x = player_pos.x as i32;
-    let player_tile_y = player_pos.y as i32;
+    let mode = movement_mode.map(|mode| *mode).unwrap_or_default();
 
     // Dynamites are stored in an array (e.g., [Coin; 1])
     for dynamite in maze_res.maze.dynamites.iter() {
-        if dynamite.x == player_tile_x && dynamite.y == player_tile_y {
+        if touches_item(
+            mode,
+            &maze_res.maze,
+            player_pos.x,
+            player_pos.y,
+            dynamite.x,
+            dynamite.y,
+        ) {
             event_writer.write(DynamiteCollisionMessage {
                 x: dynamite.x,
                 y: dynamite.y,
@@ -30,17 +39,24 @@ pub fn detect_dynamite_collision(
 /// This system listens for `DynamiteCollisionEvent` events and handles them by
 /// relocating the dynamite in the maze (so that the player can pick up another one)
 /// and updating the associated entity's component so the visual position is corrected.
+/// The collected dynamite is added to the player's inventory.
 pub fn handle_dynamite_collision(
     mut events: MessageReader<DynamiteCollisionMessage>,
     mut maze_res: ResMut<MazeResource>,
+    mut hud_state: ResMut<HudState>,
+    mut rng: ResMut<GameRng>,
     mut query: Query<&mut DynamiteComponent>,
 ) {
     for event in events.read() {
+        hud_state.dynamites += 1;
         // Relocate the dynamite in the maze.
-        maze_res.maze.relocate_dynamite(Coin {
-            x: event.x,
-            y: event.y,
-        });
+        maze_res.maze.relocate_dynamite(
+            Coin {
+                x: event.x,
+                y: event.y,
+            },
+            rng.stream(Stream::Items),
+        );
         // Now update the dynamite entity: we assume the component stores its tile coordinates.
         for mut dyn_comp in query.iter_mut() {
             if dyn_comp.x == event.x && dyn_comp.y == event.y {
@@ -1,6 +1,11 @@
-use crate::components::Player;
+#[cfg(not(feature = "std"))]
+use crate::components::MainCamera;
+use crate::components::{Player, VisualMotion};
 use crate::events::npc::NpcCollisionMessage;
+use crate::movement::{MovementMode, touches_item};
 use crate::resources::{MazeResource, PlayerPosition};
+use crate::rng::{GameRng, Stream};
+use crate::systems::motion::move_to;
 use crate::transform::UnifiedTransform;
 use bevy::prelude::*;
 
@@ -9,13 +14,20 @@ use bevy::prelude::*;
 pub fn detect_npc_collision(
     player_pos: Res<PlayerPosition>,
     maze_res: Res<MazeResource>,
+    movement_mode: Option<Res<MovementMode>>,
     mut event_writer: MessageWriter<NpcCollisionMessage>,
 ) {
-    let player_tile_x = player_pos.x as i32;
-    let player_tile_y = player_pos.y as i32;
+    let mode = movement_mode.map(|mode| *mode).unwrap_or_default();
 
     for npc in maze_res.maze.npcs.iter() {
-        if npc.x == player_tile_x && npc.y == player_tile_y {
+        if touches_item(
+            mode,
+            &maze_res.maze,
+            player_pos.x,
+            player_pos.y,
+            npc.x,
+            npc.y,
+        ) {
             event_writer.write(NpcCollisionMessage {
                 npc_x: npc.x,
                 npc_y: npc.y,
@@ -30,28 +42,34 @@ pub fn handle_npc_collision(
     mut events: MessageReader<NpcCollisionMessage>,
     mut player_pos: ResMut<PlayerPosition>,
     mut maze_res: ResMut<MazeResource>,
-    mut player_query: Query<&mut UnifiedTransform, With<Player>>,
+    mut rng: ResMut<GameRng>,
+    mut player_query: Query<(&mut UnifiedTransform, Option<&mut VisualMotion>), With<Player>>,
+    #[cfg(feature = "std")] mut camera_query: Query<
+        (&mut UnifiedTransform, Option<&mut VisualMotion>),
+        (With<Camera2d>, Without<Player>),
+    >,
+    #[cfg(not(feature = "std"))] mut camera_query: Query<
+        (&mut UnifiedTransform, Option<&mut VisualMotion>),
+        (With<MainCamera>, Without<Player>),
+    >,
 ) {
     for _event in events.read() {
         // Relocate the player.
-        let (new_x, new_y) = maze_res.maze.get_random_coordinates();
+        let (new_x, new_y) = maze_res
+            .maze
+            .get_random_coordinates(rng.stream(Stream::Effects));
         player_pos.x = new_x as f32;
         player_pos.y = new_y as f32;
 
-        if let Ok(mut transform) = player_query.single_mut() {
-            #[cfg(feature = "std")]
-            {
-                transform.translation.x = player_pos.x;
-                transform.translation.y = player_pos.y;
-            }
-            #[cfg(not(feature = "std"))]
-            {
-                transform.0.translation.x = player_pos.x;
-                transform.0.translation.y = player_pos.y;
-            }
+        // Jump straight there instead of gliding across the maze.
+        if let Ok((mut transform, motion)) = player_query.single_mut() {
+            move_to(&mut transform, motion, player_pos.x, player_pos.y, true);
+        }
+        for (mut transform, motion) in camera_query.iter_mut() {
+            move_to(&mut transform, motion, player_pos.x, player_pos.y, true);
         }
 
         // Apply penalty: relocate 5 coins.
-        maze_res.maze.relocate_coins(5);
+        maze_res.maze.relocate_coins(5, rng.stream(Stream::Items));
     }
 }
@@ -1,6 +1,8 @@
 use crate::components::WalkerComponent;
 use crate::events::walker::WalkerCollisionMessage;
+use crate::movement::{MovementMode, touches_item};
 use crate::resources::{MazeResource, PlayerPosition};
+use crate::rng::{GameRng, Stream};
 use bevy::prelude::*; // Assumes you have a WalkerComponent
 
 /// This system checks the player's current tile against all walker tiles in the maze.
@@ -8,15 +10,21 @@ use bevy::prelude::*; // Assumes you have a WalkerComponent
 pub fn detect_walker_collision(
     player_pos: Res<PlayerPosition>,
     maze_res: Res<MazeResource>,
+    movement_mode: Option<Res<MovementMode>>,
     mut event_writer: MessageWriter<WalkerCollisionMessage>,
 ) {
-    // Assume the player moves in tile increments.
-    let player_tile_x = player_pos.x as i32;
-    let player_tile_y = player_pos.y as i32;
+    let mode = movement_mode.map(|mode| *mode).unwrap_or_default();
 
     // Iterate over all walker positions stored in the maze.
     for walker in maze_res.maze.walkers.iter() {
-        if walker.x == player_tile_x && walker.y == player_tile_y {
+        if touches_item(
+            mode,
+            &maze_res.maze,
+            player_pos.x,
+            player_pos.y,
+            walker.x,
+            walker.y,
+        ) {
             event_writer.write(WalkerCollisionMessage {
                 walker_x: walker.x,
                 walker_y: walker.y,
@@ -30,11 +38,14 @@ pub fn detect_walker_collision(
 pub fn handle_walker_collision(
     mut events: MessageReader<WalkerCollisionMessage>,
     mut maze_res: ResMut<MazeResource>,
+    mut rng: ResMut<GameRng>,
     mut query: Query<&mut WalkerComponent>,
 ) {
     for event in events.read() {
         // Get a new random coordinate for the walker.
-        let (new_x, new_y) = maze_res.maze.get_random_coordinates();
+        let (new_x, new_y) = maze_res
+            .maze
+            .get_random_coordinates(rng.stream(Stream::Items));
         // Update the maze's walker array.
         for walker in maze_res.maze.walkers.iter_mut() {
             if walker.x == event.walker_x && walker.y == event.walker_y {
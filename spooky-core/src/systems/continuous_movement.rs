@@ -0,0 +1,61 @@
+#[cfg(not(feature = "std"))]
+use crate::components::MainCamera;
+use crate::components::{Player, VisualMotion};
+use crate::events::player::PlayerAnalogInputMessage;
+use crate::movement::{MovementMode, move_aabb};
+use crate::resources::{MazeResource, PlayerPosition};
+use crate::systems::motion::move_to;
+use crate::transform::UnifiedTransform;
+use bevy::prelude::*;
+use bevy::time::Time;
+
+/// Moves the player freely in `MovementMode::Continuous`; does nothing in grid mode.
+///
+/// The velocity is the analog input scaled by `max_speed`. When several sources report
+/// in the same tick (keyboard and gamepad, for example) the strongest input wins, and a
+/// tick without any input stops the player. The player's collision box is swept against
+/// the wall tiles one axis at a time, so it slides along walls instead of sticking.
+pub fn apply_continuous_movement(
+    movement_mode: Option<Res<MovementMode>>,
+    time: Res<Time>,
+    mut events: MessageReader<PlayerAnalogInputMessage>,
+    mut player_pos: ResMut<PlayerPosition>,
+    maze_res: Res<MazeResource>,
+    mut player_query: Query<(&mut UnifiedTransform, Option<&mut VisualMotion>), With<Player>>,
+    #[cfg(feature = "std")] mut camera_query: Query<
+        (&mut UnifiedTransform, Option<&mut VisualMotion>),
+        (With<Camera2d>, Without<Player>),
+    >,
+    #[cfg(not(feature = "std"))] mut camera_query: Query<
+        (&mut UnifiedTransform, Option<&mut VisualMotion>),
+        (With<MainCamera>, Without<Player>),
+    >,
+) {
+    let input = events
+        .read()
+        .map(|event| Vec2::new(event.x, event.y).clamp_length_max(1.0))
+        .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        .unwrap_or(Vec2::ZERO);
+
+    let Some(MovementMode::Continuous { max_speed }) = movement_mode.map(|mode| *mode) else {
+        return;
+    };
+    if input == Vec2::ZERO {
+        return;
+    }
+
+    let delta = input * max_speed * time.delta_secs();
+    let (new_x, new_y) = move_aabb(&maze_res.maze, player_pos.x, player_pos.y, delta.x, delta.y);
+    if (new_x, new_y) == (player_pos.x, player_pos.y) {
+        return;
+    }
+    player_pos.x = new_x;
+    player_pos.y = new_y;
+
+    if let Ok((mut transform, motion)) = player_query.single_mut() {
+        move_to(&mut transform, motion, player_pos.x, player_pos.y, false);
+    }
+    for (mut transform, motion) in camera_query.iter_mut() {
+        move_to(&mut transform, motion, player_pos.x, player_pos.y, false);
+    }
+}
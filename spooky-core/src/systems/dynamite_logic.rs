@@ -1,6 +1,7 @@
 use crate::events::dynamite::DynamiteCollisionMessage;
 use crate::maze::Coin;
 use crate::resources::MazeResource;
+use crate::rng::{GameRng, Stream};
 use bevy::prelude::*;
 
 /// This system listens for `DynamiteCollisionEvent` and relocates the dynamite
@@ -8,11 +9,15 @@ use bevy::prelude::*;
 pub fn handle_dynamite_collision(
     mut events: MessageReader<DynamiteCollisionMessage>,
     mut maze_res: ResMut<MazeResource>,
+    mut rng: ResMut<GameRng>,
 ) {
     for event in events.read() {
-        maze_res.maze.relocate_dynamite(Coin {
-            x: event.x,
-            y: event.y,
-        });
+        maze_res.maze.relocate_dynamite(
+            Coin {
+                x: event.x,
+                y: event.y,
+            },
+            rng.stream(Stream::Items),
+        );
     }
 }
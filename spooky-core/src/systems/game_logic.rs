@@ -1,6 +1,18 @@
 use crate::resources::MazeResource;
+use crate::rng::{GameRng, Stream};
+use crate::systems::hud::HudState;
+use crate::systems::player_action::TELEPORT_READY;
 use bevy::prelude::*;
 
-pub fn update_game(mut maze_resource: ResMut<MazeResource>) {
-    maze_resource.maze.move_npcs();
+pub fn update_game(
+    mut maze_resource: ResMut<MazeResource>,
+    mut hud_state: ResMut<HudState>,
+    mut rng: ResMut<GameRng>,
+) {
+    maze_resource.maze.move_npcs(rng.stream(Stream::Npc));
+
+    // Recharge the teleport one step per tick.
+    if hud_state.teleport_countdown < TELEPORT_READY {
+        hud_state.teleport_countdown += 1;
+    }
 }
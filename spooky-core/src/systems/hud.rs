@@ -1,12 +1,17 @@
 use bevy::prelude::*;
 
+use crate::seed_code::SeedCode;
+
 /// A resource storing the current HUD values.
-#[derive(Resource)]
+#[derive(Resource, Clone, Debug, PartialEq)]
 pub struct HudState {
     pub coins_left: u32,
     pub teleport_countdown: u32,
     pub walker_timer: u32,
     pub dynamites: u32,
+    pub level: u32,
+    /// Code of the maze seed, set by `setup`; `None` for seeds that are no code.
+    pub seed: Option<SeedCode>,
 }
 
 impl Default for HudState {
@@ -16,6 +21,8 @@ impl Default for HudState {
             teleport_countdown: 100,
             walker_timer: 0,
             dynamites: 0,
+            level: 1,
+            seed: None,
         }
     }
 }
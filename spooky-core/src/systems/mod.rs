@@ -1,7 +1,26 @@
 pub mod collisions;
+pub mod continuous_movement;
 pub mod dynamite_logic;
 pub mod game_logic;
 pub mod hud;
+pub mod motion;
 pub mod npc_logic;
+pub mod player_action;
 pub mod process_player_input;
+pub mod queued_input;
 pub mod setup;
+pub mod tilt_input;
+
+use bevy::prelude::SystemSet;
+
+/// Stages of a game tick in `FixedUpdate`, run in this order: input sources write
+/// their messages in `Input`, a `replay::Recorder` reads them in `Record` and the game
+/// systems of `board::add_game_systems` handle them in `Logic`. Pinning the input
+/// before the logic makes every message take effect in the tick it was written in,
+/// which a replay depends on.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TickSet {
+    Input,
+    Record,
+    Logic,
+}
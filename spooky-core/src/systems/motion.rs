@@ -0,0 +1,75 @@
+use crate::components::VisualMotion;
+use crate::transform::{UnifiedTransform, translation_mut};
+use bevy::prelude::*;
+use bevy::time::Time;
+
+/// How long (in seconds) the visual transition of one logical step takes.
+/// It should match the game tick, e.g. 0.1 s for the 10 Hz fixed update on desktop.
+#[derive(Resource, Clone, Debug)]
+pub struct StepInterpolation {
+    pub duration: f32,
+}
+
+impl Default for StepInterpolation {
+    fn default() -> Self {
+        Self { duration: 0.1 }
+    }
+}
+
+/// Move an entity to a new logical position. With a `VisualMotion` the transform glides
+/// there from wherever it is drawn right now; without one, or when `snap` is set
+/// (teleports, relocation after being caught), it jumps immediately.
+pub fn move_to(
+    transform: &mut UnifiedTransform,
+    motion: Option<Mut<VisualMotion>>,
+    x: f32,
+    y: f32,
+    snap: bool,
+) {
+    let translation = translation_mut(transform);
+    match motion {
+        Some(mut motion) if !snap => {
+            motion.from = translation.truncate();
+            motion.to = Vec2::new(x, y);
+            motion.elapsed = 0.0;
+        }
+        motion => {
+            if let Some(mut motion) = motion {
+                *motion = VisualMotion::at(x, y);
+            }
+            translation.x = x;
+            translation.y = y;
+        }
+    }
+}
+
+/// Advance all running visual motions and write the interpolated positions to the
+/// transforms. Runs every frame, independently of the game tick.
+pub fn interpolate_motion(
+    time: Res<Time>,
+    interpolation: Option<Res<StepInterpolation>>,
+    mut query: Query<(&mut UnifiedTransform, &mut VisualMotion)>,
+) {
+    let duration = interpolation
+        .map(|interpolation| interpolation.duration)
+        .unwrap_or_else(|| StepInterpolation::default().duration);
+
+    for (mut transform, mut motion) in query.iter_mut() {
+        if motion.from == motion.to {
+            continue;
+        }
+        motion.elapsed += time.delta_secs();
+        let t = if duration > 0.0 {
+            (motion.elapsed / duration).min(1.0)
+        } else {
+            1.0
+        };
+        let position = motion.from.lerp(motion.to, t);
+        let translation = translation_mut(&mut transform);
+        translation.x = position.x;
+        translation.y = position.y;
+        if t >= 1.0 {
+            motion.from = motion.to;
+        }
+    }
+}
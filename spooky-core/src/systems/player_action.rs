@@ -0,0 +1,68 @@
+#[cfg(not(feature = "std"))]
+use crate::components::MainCamera;
+use crate::components::{Player, VisualMotion};
+use crate::events::action::{PlayerAction, PlayerActionMessage};
+use crate::resources::{MazeResource, PlayerPosition};
+use crate::rng::{GameRng, Stream};
+use crate::systems::hud::HudState;
+use crate::systems::motion::move_to;
+use crate::transform::UnifiedTransform;
+use bevy::prelude::*;
+use log::info;
+
+/// The teleport becomes available again once the countdown reaches this value.
+pub const TELEPORT_READY: u32 = 100;
+
+/// Handles `PlayerActionMessage`s coming from any input source.
+/// Teleport moves the player to a random walkable tile once the teleport is charged,
+/// dynamite blasts the walls around the player if one was collected.
+pub fn handle_player_action(
+    mut events: MessageReader<PlayerActionMessage>,
+    mut player_pos: ResMut<PlayerPosition>,
+    mut maze_res: ResMut<MazeResource>,
+    mut hud_state: ResMut<HudState>,
+    mut rng: ResMut<GameRng>,
+    mut player_query: Query<(&mut UnifiedTransform, Option<&mut VisualMotion>), With<Player>>,
+    #[cfg(feature = "std")] mut camera_query: Query<
+        (&mut UnifiedTransform, Option<&mut VisualMotion>),
+        (With<Camera2d>, Without<Player>),
+    >,
+    #[cfg(not(feature = "std"))] mut camera_query: Query<
+        (&mut UnifiedTransform, Option<&mut VisualMotion>),
+        (With<MainCamera>, Without<Player>),
+    >,
+) {
+    for event in events.read() {
+        match event.action {
+            PlayerAction::Teleport => {
+                if hud_state.teleport_countdown < TELEPORT_READY {
+                    info!("Teleport not ready ({})", hud_state.teleport_countdown);
+                    continue;
+                }
+                let (new_x, new_y) = maze_res
+                    .maze
+                    .get_random_coordinates(rng.stream(Stream::Effects));
+                player_pos.x = new_x as f32;
+                player_pos.y = new_y as f32;
+                hud_state.teleport_countdown = 0;
+
+                // Jump straight there instead of gliding across the maze.
+                if let Ok((mut transform, motion)) = player_query.single_mut() {
+                    move_to(&mut transform, motion, player_pos.x, player_pos.y, true);
+                }
+                for (mut transform, motion) in camera_query.iter_mut() {
+                    move_to(&mut transform, motion, player_pos.x, player_pos.y, true);
+                }
+            }
+            PlayerAction::PlaceDynamite => {
+                if hud_state.dynamites == 0 {
+                    continue;
+                }
+                hud_state.dynamites -= 1;
+                maze_res
+                    .maze
+                    .place_dynamite(player_pos.x as i32, player_pos.y as i32);
+            }
+        }
+    }
+}
@@ -1,76 +1,81 @@
 #[cfg(not(feature = "std"))]
 use crate::components::MainCamera;
-use crate::components::Player;
+use crate::components::{Player, VisualMotion};
 use crate::events::player::PlayerInputMessage;
+use crate::movement::{MovementMode, MovementRules, resolve_step};
 use crate::resources::{MazeResource, PlayerPosition};
+use crate::systems::motion::move_to;
 use bevy::prelude::*;
 
 // Use our unified transform type alias.
 use crate::transform::UnifiedTransform;
 use log::info;
 
-/// Process player input events: update the logical player position and adjust
+/// Process player input events: update the logical player position and retarget
 /// both the player's and camera's transform so that the player remains centered.
-/// Movement is only applied if the new coordinates do not collide with a wall.
+/// Entities with a `VisualMotion` glide to the new tile instead of jumping.
+/// Each step is checked per axis by `resolve_step`, which also slides along walls
+/// according to `MovementRules` (the defaults are used when the resource is missing).
+/// In `MovementMode::Continuous` the step input is ignored and the player is moved by
+/// `apply_continuous_movement` instead.
 pub fn process_player_input(
     mut events: MessageReader<PlayerInputMessage>,
     mut player_pos: ResMut<PlayerPosition>,
     maze_res: Res<MazeResource>,
-    mut player_query: Query<&mut UnifiedTransform, With<Player>>,
+    movement_rules: Option<Res<MovementRules>>,
+    movement_mode: Option<Res<MovementMode>>,
+    mut player_query: Query<(&mut UnifiedTransform, Option<&mut VisualMotion>), With<Player>>,
     #[cfg(feature = "std")] mut camera_query: Query<
-        &mut UnifiedTransform,
+        (&mut UnifiedTransform, Option<&mut VisualMotion>),
         (With<Camera2d>, Without<Player>),
     >,
     #[cfg(not(feature = "std"))] mut camera_query: Query<
-        &mut UnifiedTransform,
+        (&mut UnifiedTransform, Option<&mut VisualMotion>),
         (With<MainCamera>, Without<Player>),
     >,
 ) {
-    for event in events.read() {
-        // Calculate candidate new position.
-        let candidate_x = player_pos.x + event.dx;
-        let candidate_y = player_pos.y + event.dy;
+    if matches!(
+        movement_mode.map(|mode| *mode),
+        Some(MovementMode::Continuous { .. })
+    ) {
+        events.clear();
+        return;
+    }
+
+    let rules = movement_rules
+        .map(|rules| rules.clone())
+        .unwrap_or_default();
 
-        // Check for wall collision.
-        if maze_res
-            .maze
-            .check_wall_collision(candidate_x as i32, candidate_y as i32)
-        {
-            // Optionally log the collision, then skip updating.
-            info!("Collision detected at ({}, {})", candidate_x, candidate_y);
+    for event in events.read() {
+        // Resolve the step against the walls, possibly sliding along them.
+        let Some((new_x, new_y)) = resolve_step(
+            &maze_res.maze,
+            player_pos.x,
+            player_pos.y,
+            event.dx,
+            event.dy,
+            &rules,
+        ) else {
+            info!(
+                "Collision detected at ({}, {})",
+                player_pos.x + event.dx,
+                player_pos.y + event.dy
+            );
             continue;
-        }
+        };
 
-        // No collision: update the logical player position.
-        player_pos.x = candidate_x;
-        player_pos.y = candidate_y;
+        // Update the logical player position.
+        player_pos.x = new_x;
+        player_pos.y = new_y;
 
         // Update the player's transform.
-        if let Ok(mut transform) = player_query.single_mut() {
-            #[cfg(feature = "std")]
-            {
-                transform.translation.x = player_pos.x;
-                transform.translation.y = player_pos.y;
-            }
-            #[cfg(not(feature = "std"))]
-            {
-                transform.0.translation.x = player_pos.x;
-                transform.0.translation.y = player_pos.y;
-            }
+        if let Ok((mut transform, motion)) = player_query.single_mut() {
+            move_to(&mut transform, motion, player_pos.x, player_pos.y, false);
         }
 
         // Update the camera's transform so that the player remains centered.
-        for mut transform in camera_query.iter_mut() {
-            #[cfg(feature = "std")]
-            {
-                transform.translation.x = player_pos.x;
-                transform.translation.y = player_pos.y;
-            }
-            #[cfg(not(feature = "std"))]
-            {
-                transform.0.translation.x = player_pos.x;
-                transform.0.translation.y = player_pos.y;
-            }
+        for (mut transform, motion) in camera_query.iter_mut() {
+            move_to(&mut transform, motion, player_pos.x, player_pos.y, false);
         }
     }
 }
@@ -0,0 +1,54 @@
+use alloc::vec::Vec;
+use bevy::prelude::*;
+
+use crate::events::action::{PlayerAction, PlayerActionMessage};
+use crate::events::player::PlayerInputMessage;
+use crate::input_buffer::{InputBuffer, InputRepeatConfig};
+use crate::resources::MazeResource;
+
+/// Input from sources that only report key presses, such as a terminal or the
+/// buttons and key events of a web page, collected between two game ticks.
+#[derive(Resource, Default)]
+pub struct QueuedInput {
+    moves: InputBuffer,
+    actions: Vec<PlayerAction>,
+}
+
+impl QueuedInput {
+    /// Queue a one-tile move; `dy` is in world coordinates.
+    pub fn press(&mut self, dx: i32, dy: i32) {
+        self.moves.press(dx, dy);
+    }
+
+    pub fn action(&mut self, action: PlayerAction) {
+        self.actions.push(action);
+    }
+
+    /// Drop everything that is queued.
+    pub fn clear(&mut self) {
+        self.moves.clear();
+        self.actions.clear();
+    }
+}
+
+/// Hands the queued input to the game: one move per tick, in the order the keys
+/// were pressed, and all actions.
+pub fn dispatch_queued_input(
+    mut input: ResMut<QueuedInput>,
+    time: Res<Time>,
+    maze_res: Res<MazeResource>,
+    mut input_writer: MessageWriter<PlayerInputMessage>,
+    mut action_writer: MessageWriter<PlayerActionMessage>,
+) {
+    let maze = &maze_res.maze;
+    let config = InputRepeatConfig::default();
+    if let Some((dx, dy)) = input.moves.next_move(time.elapsed_secs(), &config) {
+        input_writer.write(PlayerInputMessage {
+            dx: dx as f32 * maze.tile_width as f32,
+            dy: dy as f32 * maze.tile_height as f32,
+        });
+    }
+    for action in input.actions.drain(..) {
+        action_writer.write(PlayerActionMessage { action });
+    }
+}
@@ -1,9 +1,12 @@
 // Common Bevy imports.
-use crate::components::Player;
 #[cfg(feature = "std")]
-use crate::components::{CoinComponent, NpcComponent};
+use crate::components::CoinComponent;
+use crate::components::{NpcComponent, Player, VisualMotion};
 use crate::maze::Maze;
-use crate::resources::{MazeResource, MazeSeed, PlayerPosition};
+use crate::resources::{MazeConfig, MazeResource, MazeSeed, PlayerPosition};
+use crate::rng::{GameRng, Stream};
+use crate::seed_code::SeedCode;
+use crate::systems::hud::HudState;
 use bevy::prelude::*;
 use bevy_math::Vec3;
 use bevy_transform::prelude::Transform;
@@ -45,6 +48,8 @@ impl TextureAssets {
 
 // Embedded (no_std) mode: load embedded BMP images via tinybmp.
 #[cfg(not(feature = "std"))]
+use crate::sprite_buf::MAGIC_PINK;
+#[cfg(not(feature = "std"))]
 use bevy::prelude::Resource;
 #[cfg(not(feature = "std"))]
 use embedded_graphics::pixelcolor::Rgb565;
@@ -62,6 +67,9 @@ pub struct TextureAssets {
     pub coin: Option<Bmp<'static, Rgb565>>,
     pub walker: Option<Bmp<'static, Rgb565>>,
     pub dynamite: Option<Bmp<'static, Rgb565>>,
+    pub npc: Option<Bmp<'static, Rgb565>>,
+    /// Colour the renderer treats as transparent in the sprites.
+    pub transparency_key: Option<Rgb565>,
 }
 
 #[cfg(not(feature = "std"))]
@@ -97,17 +105,44 @@ impl TextureAssets {
                 Bmp::<Rgb565>::from_slice(include_bytes!("../../../assets/img/dynamite.bmp"))
                     .unwrap(),
             ),
+            npc: Some(
+                Bmp::<Rgb565>::from_slice(include_bytes!("../../../assets/img/npc.bmp")).unwrap(),
+            ),
+            transparency_key: Some(MAGIC_PINK),
+        }
+    }
+
+    /// The bitmap of a sprite, `None` when it is not loaded.
+    pub fn sprite(&self, texture: TextureId) -> Option<&Bmp<'static, Rgb565>> {
+        match texture {
+            TextureId::Ghost => self.ghost.as_ref(),
+            TextureId::Coin => self.coin.as_ref(),
+            TextureId::Walker => self.walker.as_ref(),
+            TextureId::Dynamite => self.dynamite.as_ref(),
+            TextureId::Npc => self.npc.as_ref(),
         }
     }
 }
 
 #[cfg(not(feature = "std"))]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TextureId {
     Ghost,
     Coin,
     Walker,
     Dynamite,
+    Npc,
+}
+
+#[cfg(not(feature = "std"))]
+impl TextureId {
+    pub const ALL: [TextureId; 5] = [
+        TextureId::Ghost,
+        TextureId::Coin,
+        TextureId::Walker,
+        TextureId::Dynamite,
+        TextureId::Npc,
+    ];
 }
 
 #[cfg(not(feature = "std"))]
@@ -126,6 +161,8 @@ pub fn setup(
     mut commands: Commands,
     #[cfg(feature = "std")] asset_server: Res<AssetServer>,
     maze_seed: Res<MazeSeed>, // NEW: Inject the seed resource
+    maze_config: Option<Res<MazeConfig>>,
+    hud_state: Option<ResMut<HudState>>,
 ) {
     // Load textures conditionally.
     #[cfg(feature = "std")]
@@ -134,12 +171,32 @@ pub fn setup(
     commands.insert_resource(TextureAssets::load());
 
     // Create the maze.
-    let mut maze = Maze::new(64, 64, maze_seed.0);
-    maze.generate_maze(32, 32);
-    maze.generate_coins();
-    maze.generate_walkers();
-    maze.generate_dynamites();
-    maze.generate_npcs();
+    let maze_config = maze_config.map(|config| *config).unwrap_or_default();
+    let mut rng = GameRng::from_maze_seed(maze_seed.0);
+    let mut maze = Maze::new(64, 64);
+    if maze_config.static_layout {
+        maze.load_static_layout();
+    } else {
+        let fields = |size: u32| size.clamp(MazeConfig::MIN_SIZE, MazeConfig::MAX_SIZE) as usize;
+        maze.generate_maze_with(
+            fields(maze_config.width) + 1,
+            fields(maze_config.height) + 1,
+            maze_config.algorithm,
+            rng.stream(Stream::Layout),
+        );
+    }
+    let items = rng.stream(Stream::Items);
+    maze.place_coins(maze_config.coins, items);
+    maze.generate_walkers(items);
+    maze.generate_dynamites(items);
+    maze.place_npcs(maze_config.npcs as usize, rng.stream(Stream::Npc));
+    commands.insert_resource(rng);
+
+    // Show the seed code and the coins on the HUD.
+    if let Some(mut hud_state) = hud_state {
+        hud_state.seed = maze_seed.0.as_ref().and_then(SeedCode::from_seed);
+        hud_state.coins_left = maze.coin_counter;
+    }
 
     // Compute playable bounds.
     let (left, bottom, _right, _top) = maze.playable_bounds();
@@ -164,6 +221,7 @@ pub fn setup(
         commands.spawn((
             Sprite::from_image(textures.ghost.clone()),
             Transform::from_translation(player_start),
+            VisualMotion::at(initial_x, initial_y),
             Player,
         ));
     }
@@ -174,6 +232,7 @@ pub fn setup(
             NoStdSprite {
                 texture: TextureId::Ghost,
             },
+            VisualMotion::at(initial_x, initial_y),
             Player,
         ));
     }
@@ -247,9 +306,9 @@ pub fn setup(
     }
 
     // <-- NEW: Spawn NPCs.
-    #[cfg(feature = "std")]
     for (i, npc) in maze_for_entities.npcs.iter().enumerate() {
         if npc.x != -1 && npc.y != -1 {
+            #[cfg(feature = "std")]
             {
                 // Choose an appropriate z-coordinate (e.g., 5.0) so that NPCs are drawn in front of coins
                 // but behind the player if that’s your design.
@@ -261,6 +320,27 @@ pub fn setup(
                         x: npc.x,
                         y: npc.y,
                     },
+                    VisualMotion::at(npc.x as f32, npc.y as f32),
+                ));
+            }
+            #[cfg(not(feature = "std"))]
+            {
+                // The embedded renderer draws NPCs at their interpolated transform.
+                commands.spawn((
+                    NoStdTransform(Transform::from_translation(Vec3::new(
+                        npc.x as f32,
+                        npc.y as f32,
+                        5.0,
+                    ))),
+                    NoStdSprite {
+                        texture: TextureId::Npc,
+                    },
+                    NpcComponent {
+                        index: i,
+                        x: npc.x,
+                        y: npc.y,
+                    },
+                    VisualMotion::at(npc.x as f32, npc.y as f32),
                 ));
             }
         }
@@ -317,6 +397,7 @@ pub fn setup(
         commands.spawn((
             Camera2d::default(),
             Transform::from_translation(Vec3::new(initial_x, initial_y, 100.0)),
+            VisualMotion::at(initial_x, initial_y),
         ));
     }
 }
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::events::player::{PlayerAnalogInputMessage, PlayerInputMessage};
+use crate::movement::MovementMode;
+use crate::resources::MazeResource;
+
+/// Tilt (in g) that is ignored on boards that do not set their own threshold.
+pub const DEFAULT_THRESHOLD: f32 = 0.2;
+/// Tilt (in g) that counts as full deflection in continuous movement mode.
+const FULL_TILT: f32 = 0.6;
+
+/// The latest accelerometer reading in g, rotated to the screen: positive `x` tilts
+/// right, positive `y` tilts toward the bottom of the screen. Boards that go through
+/// `board::run_game` only fill this resource; `dispatch_tilt_input` turns it into
+/// player input.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tilt {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Tilt below this (in g) is ignored. `build_app` takes it from
+/// `Board::tilt_threshold`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct TiltThreshold(pub f32);
+
+impl Default for TiltThreshold {
+    fn default() -> Self {
+        Self(DEFAULT_THRESHOLD)
+    }
+}
+
+/// Turns the tilt into one-tile steps once it passes the `TiltThreshold`. In
+/// continuous movement mode it also sends analog input, where the speed grows with
+/// the tilt up to `FULL_TILT`.
+pub fn dispatch_tilt_input(
+    tilt: Res<Tilt>,
+    threshold: Res<TiltThreshold>,
+    maze_res: Res<MazeResource>,
+    movement_mode: Option<Res<MovementMode>>,
+    mut event_writer: MessageWriter<PlayerInputMessage>,
+    mut analog_writer: MessageWriter<PlayerAnalogInputMessage>,
+) {
+    let threshold = threshold.0;
+    let step_x = maze_res.maze.tile_width as f32;
+    let step_y = maze_res.maze.tile_height as f32;
+    let mut dx = 0.0;
+    let mut dy = 0.0;
+
+    if tilt.x.abs() > threshold {
+        dx = if tilt.x > 0.0 { step_x } else { -step_x };
+    }
+    if tilt.y.abs() > threshold {
+        dy = if tilt.y > 0.0 { step_y } else { -step_y };
+    }
+    if dx.abs() > f32::EPSILON || dy.abs() > f32::EPSILON {
+        event_writer.write(PlayerInputMessage { dx, dy });
+    }
+
+    if !matches!(
+        movement_mode.map(|mode| *mode),
+        Some(MovementMode::Continuous { .. })
+    ) {
+        return;
+    }
+    let analog = |value: f32| {
+        let magnitude = ((value.abs() - threshold) / (FULL_TILT - threshold)).clamp(0.0, 1.0);
+        magnitude.copysign(value)
+    };
+    analog_writer.write(PlayerAnalogInputMessage {
+        x: analog(tilt.x),
+        y: analog(tilt.y),
+    });
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use embedded_graphics::prelude::{Point, Size};
+use embedded_graphics::primitives::Rectangle;
+
+use crate::events::action::PlayerAction;
+
+/// The result of mapping a touch gesture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TouchAction {
+    /// Move by one tile. `dx`/`dy` are -1, 0 or 1; a positive dy moves down,
+    /// as world y grows down the screen.
+    Move { dx: i32, dy: i32 },
+    /// Trigger one of the on-screen buttons.
+    Action(PlayerAction),
+}
+
+/// Translates touch-screen points (in screen pixels, origin top-left) into game input.
+///
+/// A short tap moves the ghost towards the tapped side of the screen, a swipe moves it
+/// in the swipe direction, and taps on the two buttons in the bottom corners trigger
+/// teleport (left) and dynamite (right). The board only needs to forward points with
+/// `press` while the finger is down and call `release` once it is lifted.
+#[derive(Resource, Clone, Debug)]
+pub struct TouchMapper {
+    pub width: i32,
+    pub height: i32,
+    /// Edge length of the square action buttons.
+    pub button_size: i32,
+    /// Minimal travel distance (in pixels) for a gesture to count as a swipe.
+    pub swipe_threshold: i32,
+    start: Option<Point>,
+    last: Option<Point>,
+}
+
+impl TouchMapper {
+    /// Create a mapper for a display of the given size.
+    pub fn new(width: u32, height: u32) -> Self {
+        let shorter = width.min(height) as i32;
+        Self {
+            width: width as i32,
+            height: height as i32,
+            button_size: shorter / 5,
+            swipe_threshold: shorter / 8,
+            start: None,
+            last: None,
+        }
+    }
+
+    /// Screen area of the teleport button (bottom-left corner).
+    pub fn teleport_button(&self) -> Rectangle {
+        Rectangle::new(
+            Point::new(0, self.height - self.button_size),
+            Size::new(self.button_size as u32, self.button_size as u32),
+        )
+    }
+
+    /// Screen area of the dynamite button (bottom-right corner).
+    pub fn dynamite_button(&self) -> Rectangle {
+        Rectangle::new(
+            Point::new(
+                self.width - self.button_size,
+                self.height - self.button_size,
+            ),
+            Size::new(self.button_size as u32, self.button_size as u32),
+        )
+    }
+
+    /// Record a touch point while the finger is on the screen.
+    pub fn press(&mut self, x: i32, y: i32) {
+        let point = Point::new(x, y);
+        if self.start.is_none() {
+            self.start = Some(point);
+        }
+        self.last = Some(point);
+    }
+
+    /// Finish the current gesture and return the resulting action, if any.
+    pub fn release(&mut self) -> Option<TouchAction> {
+        let start = self.start.take()?;
+        let end = self.last.take().unwrap_or(start);
+        let delta = end - start;
+        if delta.x.abs().max(delta.y.abs()) >= self.swipe_threshold {
+            Some(Self::direction(delta.x, delta.y))
+        } else {
+            Some(self.map_tap(start.x, start.y))
+        }
+    }
+
+    /// Map a single tap to an action: buttons first, then the side of the screen.
+    pub fn map_tap(&self, x: i32, y: i32) -> TouchAction {
+        let point = Point::new(x, y);
+        if self.teleport_button().contains(point) {
+            return TouchAction::Action(PlayerAction::Teleport);
+        }
+        if self.dynamite_button().contains(point) {
+            return TouchAction::Action(PlayerAction::PlaceDynamite);
+        }
+        // Split the screen along its diagonals, so that each edge owns one quadrant.
+        // Scale by the opposite dimension to keep the split correct on non-square panels.
+        let rel_x = (x - self.width / 2) * self.height;
+        let rel_y = (y - self.height / 2) * self.width;
+        Self::direction(rel_x, rel_y)
+    }
+
+    /// Pick the dominant axis of a vector and turn it into a one-tile move.
+    fn direction(dx: i32, dy: i32) -> TouchAction {
+        if dx.abs() >= dy.abs() {
+            TouchAction::Move {
+                dx: dx.signum(),
+                dy: 0,
+            }
+        } else {
+            TouchAction::Move {
+                dx: 0,
+                dy: dy.signum(),
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use bevy_math::Vec3;
+
 // When std is available, we simply alias to the Bevy Transform.
 #[cfg(feature = "std")]
 pub type UnifiedTransform = bevy_transform::components::Transform;
@@ -5,3 +7,15 @@ pub type UnifiedTransform = bevy_transform::components::Transform;
 // When in no_std mode, alias to your own type.
 #[cfg(not(feature = "std"))]
 pub type UnifiedTransform = crate::systems::setup::NoStdTransform;
+
+/// Mutable access to the translation of either transform flavour.
+pub(crate) fn translation_mut(transform: &mut UnifiedTransform) -> &mut Vec3 {
+    #[cfg(feature = "std")]
+    {
+        &mut transform.translation
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        &mut transform.0.translation
+    }
+}
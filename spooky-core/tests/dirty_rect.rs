@@ -0,0 +1,132 @@
+//! Flushing only the dirty regions must leave the display exactly as flushing the
+//! whole framebuffer every frame does. Runs on the host with the no_std renderer.
+
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics_framebuf::FrameBuf;
+use spooky_core::heapbuffer::HeapBuffer;
+use spooky_core::maze::{Coin, Maze};
+use spooky_core::render::dirty::{DirtyTracker, Flush, flush};
+use spooky_core::render::render_frame;
+use spooky_core::render::zoom::Zoom;
+use spooky_core::rng::{GameRng, Stream};
+use spooky_core::systems::hud::HudState;
+use spooky_core::systems::setup::TextureAssets;
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 240;
+const PIXELS: usize = WIDTH * HEIGHT;
+
+type Screen = FrameBuf<Rgb565, HeapBuffer<Rgb565, PIXELS>>;
+
+fn screen() -> Screen {
+    FrameBuf::new(
+        HeapBuffer::new(Box::new([Rgb565::BLACK; PIXELS])),
+        WIDTH,
+        HEIGHT,
+    )
+}
+
+struct Scene {
+    maze: Maze,
+    hud: HudState,
+    camera: Point,
+    npcs: Vec<Point>,
+}
+
+struct Harness {
+    textures: TextureAssets,
+    frame: Screen,
+    full_display: Screen,
+    dirty_display: Screen,
+    tracker: DirtyTracker,
+}
+
+impl Harness {
+    fn new() -> Self {
+        Self {
+            textures: TextureAssets::load(),
+            frame: screen(),
+            full_display: screen(),
+            dirty_display: screen(),
+            tracker: DirtyTracker::default(),
+        }
+    }
+
+    /// Render one frame, flush it both ways and check that the displays agree.
+    fn frame(&mut self, scene: &Scene) -> Flush {
+        let summary = render_frame(
+            &mut self.frame,
+            &scene.maze,
+            &self.textures,
+            scene.camera,
+            Zoom::Normal,
+            scene.npcs.iter().copied(),
+            &scene.hud,
+        )
+        .unwrap();
+        flush(&mut self.full_display, &self.frame, &Flush::Full).unwrap();
+        let regions = self.tracker.update(&summary, &scene.maze, &scene.hud);
+        flush(&mut self.dirty_display, &self.frame, &regions).unwrap();
+
+        assert!(
+            self.full_display.data[..] == self.dirty_display.data[..],
+            "dirty flush diverged from the full frame after {:?}",
+            regions
+        );
+        regions
+    }
+}
+
+fn scene() -> Scene {
+    let mut rng = GameRng::new([7; 32]);
+    let mut maze = Maze::new(64, 64);
+    maze.generate_maze(32, 32, rng.stream(Stream::Layout));
+    maze.generate_coins(rng.stream(Stream::Items));
+    maze.generate_walkers(rng.stream(Stream::Items));
+    maze.generate_dynamites(rng.stream(Stream::Items));
+    maze.generate_npcs(rng.stream(Stream::Npc));
+    let camera = Point::new(11 * 16, 10 * 16);
+    // Put a coin right next to the ghost so that collecting it is visible.
+    maze.coins[0] = Coin {
+        x: camera.x + 32,
+        y: camera.y,
+    };
+    Scene {
+        maze,
+        hud: HudState::default(),
+        camera,
+        npcs: vec![camera + Point::new(-48, 16), camera + Point::new(64, -32)],
+    }
+}
+
+#[test]
+fn dirty_flush_matches_full_frame() {
+    let mut harness = Harness::new();
+    let mut scene = scene();
+
+    assert_eq!(harness.frame(&scene), Flush::Full, "first frame");
+    assert_eq!(harness.frame(&scene), Flush::None, "unchanged frame");
+
+    // NPCs glide by a few pixels.
+    for npc in scene.npcs.iter_mut() {
+        *npc += Point::new(4, 0);
+    }
+    assert!(matches!(harness.frame(&scene), Flush::Regions(_)));
+
+    // The coin is collected: the sprite disappears and the HUD changes.
+    let coin = scene.maze.coins[0];
+    scene.maze.remove_coin(coin);
+    scene.hud.coins_left -= 1;
+    assert!(matches!(harness.frame(&scene), Flush::Regions(_)));
+
+    // Dynamite scorches the tiles around the ghost.
+    scene.maze.place_dynamite(scene.camera.x, scene.camera.y);
+    scene.hud.dynamites += 1;
+    assert!(matches!(harness.frame(&scene), Flush::Regions(_)));
+
+    // A camera move scrolls everything.
+    scene.camera += Point::new(4, 0);
+    assert_eq!(harness.frame(&scene), Flush::Full, "camera moved");
+    assert_eq!(harness.frame(&scene), Flush::None, "camera settled");
+}
@@ -0,0 +1,85 @@
+//! The random streams of a game must not influence each other, and the range helpers
+//! must cover their range evenly.
+
+use spooky_core::maze::Maze;
+use spooky_core::rng::{GameRng, Stream};
+
+fn draws(rng: &mut GameRng, stream: Stream) -> Vec<u32> {
+    (0..32).map(|_| rng.stream(stream).below(1000)).collect()
+}
+
+#[test]
+fn streams_are_independent() {
+    let mut quiet = GameRng::new([3; 32]);
+    let mut busy = GameRng::new([3; 32]);
+    // A new feature drawing extra numbers from one stream.
+    for _ in 0..100 {
+        busy.stream(Stream::Effects).below(7);
+        busy.stream(Stream::Items).seed();
+    }
+    for stream in [Stream::Layout, Stream::Npc] {
+        assert_eq!(draws(&mut quiet, stream), draws(&mut busy, stream));
+    }
+    assert_ne!(
+        draws(&mut quiet, Stream::Items),
+        draws(&mut quiet, Stream::Npc)
+    );
+}
+
+#[test]
+fn seeds_repeat_their_numbers() {
+    let mut a = GameRng::from_maze_seed(Some([9; 32]));
+    let mut b = GameRng::new([9; 32]);
+    for stream in Stream::ALL {
+        assert_eq!(draws(&mut a, stream), draws(&mut b, stream));
+    }
+    let mut other = GameRng::new([10; 32]);
+    assert_ne!(
+        draws(&mut a, Stream::Items),
+        draws(&mut other, Stream::Items)
+    );
+}
+
+#[test]
+fn ranges_are_covered_evenly() {
+    let mut rng = GameRng::new([5; 32]);
+    let stream = rng.stream(Stream::Npc);
+    let mut counts = [0u32; 3];
+    for _ in 0..30_000 {
+        let value = stream.range(-1..2);
+        assert!((-1..2).contains(&value));
+        counts[(value + 1) as usize] += 1;
+    }
+    assert!(counts.iter().all(|&count| (9_000..11_000).contains(&count)));
+
+    assert_eq!(stream.below(1), 0);
+    assert!(stream.below(u32::MAX) < u32::MAX);
+    assert_eq!(stream.range(i32::MIN..i32::MIN + 1), i32::MIN);
+    assert_eq!(stream.choose(&['x']), 'x');
+}
+
+#[test]
+fn npc_moves_do_not_touch_the_items() {
+    let mut maze = Maze::new(64, 64);
+    maze.load_static_layout();
+    let mut rng = GameRng::new([7; 32]);
+    maze.place_coins(10, rng.stream(Stream::Items));
+    maze.place_npcs(3, rng.stream(Stream::Npc));
+    for _ in 0..50 {
+        maze.move_npcs(rng.stream(Stream::Npc));
+    }
+    maze.remove_coin(maze.coins[0]);
+    maze.relocate_coins(1, rng.stream(Stream::Items));
+
+    let mut fresh = GameRng::new([7; 32]);
+    let items = fresh.stream(Stream::Items);
+    let mut placed = Maze::new(64, 64);
+    placed.load_static_layout();
+    placed.place_coins(10, items);
+    let relocated = placed.get_random_coordinates(items);
+    assert!(
+        maze.coins[..10]
+            .iter()
+            .any(|coin| (coin.x, coin.y) == relocated)
+    );
+}
@@ -0,0 +1,63 @@
+//! Coin and NPC counts and the static layout requested by `MazeConfig`.
+
+use spooky_core::maze::Maze;
+use spooky_core::resources::MazeConfig;
+use spooky_core::rng::{GameRng, Stream};
+
+fn maze() -> (Maze, GameRng) {
+    let mut maze = Maze::new(64, 64);
+    maze.load_static_layout();
+    (maze, GameRng::new([7; 32]))
+}
+
+#[test]
+fn coins_are_placed_up_to_the_count() {
+    let (mut maze, mut rng) = maze();
+    maze.place_coins(10, rng.stream(Stream::Items));
+    assert_eq!(maze.coin_counter, 10);
+    assert_eq!(maze.coin_total, 10);
+    assert!(maze.coins[..10].iter().all(|coin| coin.x != -1));
+    assert!(maze.coins[10..].iter().all(|coin| coin.x == -1));
+
+    maze.place_coins(1000, rng.stream(Stream::Items));
+    assert_eq!(maze.coin_counter, Maze::MAX_COINS);
+}
+
+#[test]
+fn relocated_coins_never_exceed_the_count() {
+    let (mut maze, mut rng) = maze();
+    maze.place_coins(10, rng.stream(Stream::Items));
+    // Coins on the same tile are collected together.
+    let coin = maze.coins[0];
+    let removed = maze.coins[..10]
+        .iter()
+        .filter(|other| other.x == coin.x && other.y == coin.y)
+        .count() as u32;
+    maze.remove_coin(coin);
+    assert_eq!(maze.coin_counter, 10 - removed);
+
+    maze.relocate_coins(removed + 5, rng.stream(Stream::Items));
+    assert_eq!(maze.coin_counter, 10);
+    assert!(maze.coins[10..].iter().all(|coin| coin.x == -1));
+}
+
+#[test]
+fn unused_npcs_stay_out_of_the_maze() {
+    let (mut maze, mut rng) = maze();
+    maze.place_npcs(2, rng.stream(Stream::Npc));
+    assert_eq!(maze.npc_count, 2);
+    for _ in 0..50 {
+        maze.move_npcs(rng.stream(Stream::Npc));
+    }
+    assert!(maze.npcs[2..].iter().all(|npc| npc.x == -1 && npc.y == -1));
+}
+
+#[test]
+fn default_config_is_the_full_maze() {
+    let config = MazeConfig::default();
+    assert_eq!(MazeConfig::tiles(config.width), 64);
+    assert_eq!(MazeConfig::tiles(config.height), 64);
+    assert_eq!(MazeConfig::tiles(MazeConfig::MIN_SIZE), 14);
+    assert_eq!(config.coins, Maze::MAX_COINS);
+    assert_eq!(config.npcs as usize, Maze::MAX_NPCS);
+}
@@ -0,0 +1,162 @@
+//! Palette conversion, transparency keys and rendering in other pixel formats.
+
+use embedded_graphics::pixelcolor::{BinaryColor, Rgb565, Rgb888};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics_framebuf::FrameBuf;
+use spooky_core::heapbuffer::VecBuffer;
+use spooky_core::maze::Maze;
+use spooky_core::render::dirty::{Flush, flush};
+use spooky_core::render::pixel_format::{Direct, Monochrome, Tone, luma};
+use spooky_core::render::tile_cache::TileCache;
+use spooky_core::render::zoom::Zoom;
+use spooky_core::render::{render_frame, render_frame_cached};
+use spooky_core::rng::{GameRng, Stream};
+use spooky_core::sprite_buf::{MAGIC_PINK, SpriteBuf};
+use spooky_core::systems::hud::HudState;
+use spooky_core::systems::setup::{TextureAssets, TextureId};
+
+const WIDTH: usize = 128;
+const HEIGHT: usize = 64;
+
+#[test]
+fn luma_spans_the_full_range() {
+    assert_eq!(luma(Rgb565::BLACK), 0);
+    assert_eq!(luma(Rgb565::WHITE), 255);
+    assert!(luma(Rgb565::GREEN) > luma(Rgb565::RED));
+    assert!(luma(Rgb565::RED) > luma(Rgb565::BLUE));
+}
+
+#[test]
+fn dithering_follows_brightness() {
+    let lit = |color: Rgb565| {
+        Rectangle::new(Point::zero(), Size::new(4, 4))
+            .points()
+            .filter(|point| Tone::Dither.is_on(color, *point))
+            .count()
+    };
+    assert_eq!(lit(Rgb565::BLACK), 0);
+    assert_eq!(lit(Rgb565::WHITE), 16);
+    let gray = Rgb565::from(Rgb888::new(128, 128, 128));
+    assert_eq!(lit(gray), 8);
+    // The pattern repeats every four pixels.
+    assert_eq!(
+        Tone::Dither.is_on(gray, Point::new(1, 2)),
+        Tone::Dither.is_on(gray, Point::new(5, -2))
+    );
+}
+
+#[test]
+fn transparency_key_is_configurable() {
+    let mut frame = FrameBuf::new([Rgb565::BLACK; 4], 2, 2);
+    let pixels = [
+        Pixel(Point::new(0, 0), MAGIC_PINK),
+        Pixel(Point::new(1, 0), Rgb565::RED),
+    ];
+    SpriteBuf::new(&mut frame).draw_iter(pixels).unwrap();
+    assert_eq!(frame.get_color_at(Point::new(0, 0)), Rgb565::BLACK);
+    assert_eq!(frame.get_color_at(Point::new(1, 0)), Rgb565::RED);
+
+    SpriteBuf::with_key(&mut frame, Some(Rgb565::RED))
+        .draw_iter(pixels)
+        .unwrap();
+    assert_eq!(frame.get_color_at(Point::new(0, 0)), MAGIC_PINK);
+
+    // Keys work in any pixel format.
+    let mut mono = FrameBuf::new([BinaryColor::Off; 4], 2, 2);
+    SpriteBuf::with_key(&mut mono, Some(BinaryColor::Off))
+        .draw_iter([
+            Pixel(Point::new(0, 0), BinaryColor::On),
+            Pixel(Point::new(0, 0), BinaryColor::Off),
+        ])
+        .unwrap();
+    assert_eq!(mono.get_color_at(Point::new(0, 0)), BinaryColor::On);
+}
+
+#[test]
+fn monochrome_textures_are_black_and_white() {
+    let textures = TextureAssets::load();
+    for tone in [Tone::Threshold(96), Tone::Dither] {
+        let cache: TileCache<Rgb565> = TileCache::new(&textures, Zoom::Half, &Monochrome(tone));
+        let black_or_white = |pixel: &Rgb565| *pixel == Rgb565::BLACK || *pixel == Rgb565::WHITE;
+        for tile in 0..3 {
+            assert!(cache.tile(tile).unwrap().pixels.iter().all(black_or_white));
+        }
+        let coin = cache.sprite(TextureId::Coin, Zoom::Half).unwrap();
+        assert!(coin.pixels.iter().flatten().all(black_or_white));
+        // The transparency key stays transparent instead of turning black or white.
+        assert!(coin.pixels.contains(&None));
+    }
+}
+
+fn maze() -> Maze {
+    let mut rng = GameRng::new([7; 32]);
+    let mut maze = Maze::new(64, 64);
+    maze.generate_maze(32, 32, rng.stream(Stream::Layout));
+    maze.generate_coins(rng.stream(Stream::Items));
+    maze
+}
+
+fn frame<C: PixelColor>(color: C) -> FrameBuf<C, VecBuffer<C>> {
+    FrameBuf::new(VecBuffer::new(color, WIDTH * HEIGHT), WIDTH, HEIGHT)
+}
+
+#[test]
+fn frames_render_in_the_display_colour() {
+    let textures = TextureAssets::load();
+    let maze = maze();
+    let hud = HudState::default();
+    let camera = Point::new(11 * 16, 10 * 16);
+
+    // An RGB888 panel gets exactly the converted RGB565 picture, cached or not.
+    let mut rgb565 = frame(Rgb565::BLACK);
+    let mut rgb888 = frame(Rgb888::BLACK);
+    render_frame(&mut rgb565, &maze, &textures, camera, Zoom::Half, [], &hud).unwrap();
+    render_frame(&mut rgb888, &maze, &textures, camera, Zoom::Half, [], &hud).unwrap();
+    for Pixel(point, color) in &rgb565 {
+        assert_eq!(rgb888.get_color_at(point), Rgb888::from(color));
+    }
+    let cache565 = TileCache::new(&textures, Zoom::Half, &Direct);
+    let cache888 = TileCache::new(&textures, Zoom::Half, &Direct);
+    render_frame_cached(&mut rgb565, &cache565, &maze, camera, [], &hud).unwrap();
+    render_frame_cached(&mut rgb888, &cache888, &maze, camera, [], &hud).unwrap();
+    for Pixel(point, color) in &rgb565 {
+        assert_eq!(rgb888.get_color_at(point), Rgb888::from(color));
+    }
+
+    // A 1-bit panel matches the black-and-white preview of the same palette, apart
+    // from the HUD, whose colours are converted directly.
+    let palette = Monochrome(Tone::Dither);
+    let mut oled = frame(BinaryColor::Off);
+    let mut preview = frame(Rgb565::BLACK);
+    let summary = render_frame_cached(
+        &mut oled,
+        &TileCache::new(&textures, Zoom::Half, &palette),
+        &maze,
+        camera,
+        [],
+        &hud,
+    )
+    .unwrap();
+    render_frame_cached(
+        &mut preview,
+        &TileCache::new(&textures, Zoom::Half, &palette),
+        &maze,
+        camera,
+        [],
+        &hud,
+    )
+    .unwrap();
+    for Pixel(point, color) in &preview {
+        if !summary.hud.contains(point) {
+            assert_eq!(oled.get_color_at(point).is_on(), color == Rgb565::WHITE);
+        }
+    }
+    assert!(oled.data.contains(&BinaryColor::On));
+    assert!(oled.data.contains(&BinaryColor::Off));
+
+    // The finished frame is flushed to the display as is.
+    let mut display = frame(BinaryColor::Off);
+    flush(&mut display, &oled, &Flush::Full).unwrap();
+    assert!(display.data[..] == oled.data[..]);
+}
@@ -0,0 +1,83 @@
+//! Seed codes must survive being printed, shared and typed back in, and expand to
+//! the same maze seed on every front-end.
+
+use spooky_core::maze::MazeAlgorithm;
+use spooky_core::seed_code::{SeedCode, SeedCodeError};
+
+#[test]
+fn codes_round_trip_through_text() {
+    for value in [0, 1, 31, 32, 0xdead_beef, u64::MAX] {
+        let code = SeedCode(value);
+        assert_eq!(code.to_string().parse::<SeedCode>(), Ok(code));
+    }
+    assert_eq!(SeedCode(0).to_string(), "0000-0000");
+    assert_eq!(SeedCode(32).to_string(), "0000-0010");
+    assert_eq!(SeedCode((1 << 40) - 1).to_string(), "ZZZZ-ZZZZ");
+    assert_eq!(SeedCode(1 << 40).to_string(), "1-0000-0000");
+    assert_eq!(SeedCode(u64::MAX).to_string(), "F-ZZZZ-ZZZZ-ZZZZ");
+}
+
+#[test]
+fn parsing_is_forgiving_about_how_codes_are_typed() {
+    let code: SeedCode = "00SP-00KY".parse().unwrap();
+    assert_eq!(code.to_string(), "00SP-00KY");
+    assert_eq!("spooky".parse(), Ok(code));
+    assert_eq!("SP00KY".parse(), Ok(code));
+    assert_eq!("sp ooky".parse(), Ok(code));
+    assert_eq!("1".parse(), Ok(SeedCode(1)));
+    assert_eq!("i".parse(), Ok(SeedCode(1)));
+    assert_eq!("L".parse(), Ok(SeedCode(1)));
+}
+
+#[test]
+fn invalid_codes_are_rejected() {
+    assert_eq!("".parse::<SeedCode>(), Err(SeedCodeError::Empty));
+    assert_eq!("--".parse::<SeedCode>(), Err(SeedCodeError::Empty));
+    assert_eq!(
+        "SPUKY".parse::<SeedCode>(),
+        Err(SeedCodeError::InvalidDigit('U'))
+    );
+    assert_eq!(
+        "G-0000-0000-0000".parse::<SeedCode>(),
+        Err(SeedCodeError::TooLarge)
+    );
+    assert_eq!(
+        "ZZ-ZZZZ-ZZZZ-ZZZZ".parse::<SeedCode>(),
+        Err(SeedCodeError::TooLarge)
+    );
+}
+
+#[test]
+fn seeds_expand_and_are_recognised() {
+    let code = SeedCode(0x0123_4567_89ab_cdef);
+    let seed = code.to_seed();
+    assert_eq!(&seed[..8], &0x0123_4567_89ab_cdef_u64.to_le_bytes());
+    assert_eq!(&seed[8..16], &seed[..8]);
+    assert_eq!(SeedCode::from_seed(&seed), Some(code));
+
+    let mut random = seed;
+    random[31] ^= 1;
+    assert_eq!(SeedCode::from_seed(&random), None);
+}
+
+#[test]
+fn random_codes_are_short() {
+    let code = SeedCode::random(u64::MAX);
+    assert_eq!(code.to_string(), "ZZZZ-ZZZZ");
+
+    let code = SeedCode(0x42);
+    assert_eq!(SeedCode::from_entropy(&code.to_seed()), code);
+    let entropy: [u8; 32] = core::array::from_fn(|index| index as u8 + 1);
+    let code = SeedCode::from_entropy(&entropy);
+    assert!(code.0 < 1 << SeedCode::RANDOM_BITS);
+    assert_eq!(code.to_string().len(), 9);
+}
+
+#[test]
+fn algorithms_parse_by_name() {
+    for algorithm in MazeAlgorithm::ALL {
+        assert_eq!(algorithm.name().parse(), Ok(algorithm));
+    }
+    assert_eq!("Prim".parse(), Ok(MazeAlgorithm::Prim));
+    assert!("kruskal".parse::<MazeAlgorithm>().is_err());
+}
@@ -0,0 +1,69 @@
+//! Taps and swipes must move the ghost the way the finger points on screen, where y
+//! grows downwards just like world y.
+
+use spooky_core::events::action::PlayerAction;
+use spooky_core::touch::{TouchAction, TouchMapper};
+
+const UP: TouchAction = TouchAction::Move { dx: 0, dy: -1 };
+const DOWN: TouchAction = TouchAction::Move { dx: 0, dy: 1 };
+const LEFT: TouchAction = TouchAction::Move { dx: -1, dy: 0 };
+const RIGHT: TouchAction = TouchAction::Move { dx: 1, dy: 0 };
+
+/// A finger going down at `from`, passing through `to` and lifting.
+fn gesture(mapper: &mut TouchMapper, from: (i32, i32), to: (i32, i32)) -> Option<TouchAction> {
+    mapper.press(from.0, from.1);
+    mapper.press(to.0, to.1);
+    mapper.release()
+}
+
+#[test]
+fn taps_move_towards_the_tapped_edge() {
+    let mapper = TouchMapper::new(320, 240);
+    assert_eq!(mapper.map_tap(160, 0), UP);
+    assert_eq!(mapper.map_tap(160, 239), DOWN);
+    assert_eq!(mapper.map_tap(0, 120), LEFT);
+    assert_eq!(mapper.map_tap(319, 120), RIGHT);
+    // The diagonals split the wide panel, not 45 degree lines.
+    assert_eq!(mapper.map_tap(100, 30), UP);
+    assert_eq!(mapper.map_tap(60, 100), LEFT);
+    assert_eq!(mapper.map_tap(220, 210), DOWN);
+}
+
+#[test]
+fn corner_buttons_trigger_actions() {
+    let mut mapper = TouchMapper::new(320, 240);
+    let teleport = TouchAction::Action(PlayerAction::Teleport);
+    let dynamite = TouchAction::Action(PlayerAction::PlaceDynamite);
+    assert_eq!(mapper.map_tap(0, 239), teleport);
+    assert_eq!(mapper.map_tap(47, 192), teleport);
+    assert_eq!(mapper.map_tap(319, 239), dynamite);
+    assert_eq!(mapper.map_tap(272, 192), dynamite);
+    // Just outside the buttons the side of the screen counts again.
+    assert_eq!(mapper.map_tap(48, 239), DOWN);
+    assert_eq!(mapper.map_tap(0, 191), LEFT);
+
+    // A tap on a button that wobbles less than a swipe.
+    assert_eq!(gesture(&mut mapper, (10, 230), (15, 225)), Some(teleport));
+}
+
+#[test]
+fn swipes_move_in_the_swipe_direction() {
+    let mut mapper = TouchMapper::new(320, 240);
+    assert_eq!(gesture(&mut mapper, (160, 100), (160, 160)), Some(DOWN));
+    assert_eq!(gesture(&mut mapper, (160, 160), (160, 100)), Some(UP));
+    assert_eq!(gesture(&mut mapper, (100, 120), (200, 140)), Some(RIGHT));
+    assert_eq!(gesture(&mut mapper, (200, 140), (100, 120)), Some(LEFT));
+    // A swipe that starts on a button still moves.
+    assert_eq!(gesture(&mut mapper, (10, 230), (10, 100)), Some(UP));
+}
+
+#[test]
+fn short_gestures_are_taps_at_their_start() {
+    let mut mapper = TouchMapper::new(320, 240);
+    // Below the swipe threshold the gesture is a tap where the finger went down.
+    assert_eq!(gesture(&mut mapper, (160, 5), (160, 30)), Some(UP));
+    mapper.press(5, 120);
+    assert_eq!(mapper.release(), Some(LEFT));
+    // Nothing to finish without a touch.
+    assert_eq!(mapper.release(), None);
+}
@@ -0,0 +1,258 @@
+//! Host backend for the embedded renderer.
+//!
+//! `HostFrameBuffer` is an in-memory `Rgb565` framebuffer that the shared no_std
+//! render path in `spooky_core::render` draws into exactly as into a board's
+//! framebuffer. Frames can be saved as PNG, so the embedded output can be looked at
+//! and tested on Linux without flashing a board. `VirtualBoard` implements the
+//! `Board` trait on top of it, for integration tests of the whole board code path.
+
+use std::convert::Infallible;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use embedded_graphics::pixelcolor::{Rgb565, Rgb888};
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Pixel, Point, RgbColor, Size};
+use spooky_core::board::{Board, BoardResource};
+use spooky_core::components::{NpcComponent, Player};
+use spooky_core::render::pixel_format::{Direct, Monochrome};
+use spooky_core::render::tile_cache::TileCache;
+use spooky_core::render::zoom::Zoom;
+use spooky_core::render::{RawFramebuffer, camera_position, render_frame_cached, sprite_position};
+use spooky_core::resources::{MazeResource, MazeSeed, PlayerPosition};
+use spooky_core::systems::hud::HudState;
+use spooky_core::systems::setup::{NoStdTransform, TextureAssets, setup};
+use spooky_core::systems::tilt_input::Tilt;
+
+/// A row-major `Rgb565` framebuffer of any size.
+pub struct HostFrameBuffer {
+    size: Size,
+    pub pixels: Vec<Rgb565>,
+}
+
+impl HostFrameBuffer {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            pixels: vec![Rgb565::BLACK; (size.width * size.height) as usize],
+        }
+    }
+
+    /// The frame as 8-bit RGB triplets, the way it is stored in a PNG.
+    pub fn to_rgb888(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&pixel| {
+                let color = Rgb888::from(pixel);
+                [color.r(), color.g(), color.b()]
+            })
+            .collect()
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(writer, self.size.width, self.size.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.to_rgb888())
+            .map_err(io::Error::other)
+    }
+}
+
+impl OriginDimensions for HostFrameBuffer {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for HostFrameBuffer {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (width, height) = (self.size.width as i32, self.size.height as i32);
+        for Pixel(point, color) in pixels {
+            if (0..width).contains(&point.x) && (0..height).contains(&point.y) {
+                self.pixels[(point.y * width + point.x) as usize] = color;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RawFramebuffer for HostFrameBuffer {
+    fn pixels_mut(&mut self) -> &mut [Rgb565] {
+        &mut self.pixels
+    }
+}
+
+/// Read an 8-bit RGB PNG, as written by `HostFrameBuffer::save_png`.
+pub fn read_png(path: impl AsRef<Path>) -> io::Result<(Size, Vec<u8>)> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    let mut reader = decoder.read_info().map_err(io::Error::other)?;
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).map_err(io::Error::other)?;
+    if info.color_type != png::ColorType::Rgb || info.bit_depth != png::BitDepth::Eight {
+        return Err(io::Error::other("expected an 8-bit RGB PNG"));
+    }
+    data.truncate(info.buffer_size());
+    Ok((Size::new(info.width, info.height), data))
+}
+
+/// A game world set up the way the boards do it: the no_std `setup` system with a
+/// fixed `MazeSeed`, so the same seed always gives the same maze, coins and NPCs.
+pub struct HostGame {
+    pub world: World,
+}
+
+impl HostGame {
+    pub fn new(seed: [u8; 32]) -> Self {
+        let mut world = World::new();
+        world.insert_resource(MazeSeed(Some(seed)));
+        world.insert_resource(HudState::default());
+        world
+            .run_system_once(setup)
+            .expect("the setup system has no failing parameters");
+        Self { world }
+    }
+
+    /// Move the player, and with it the camera, to a world position.
+    pub fn set_player_position(&mut self, x: f32, y: f32) {
+        let mut player_pos = self.world.resource_mut::<PlayerPosition>();
+        player_pos.x = x;
+        player_pos.y = y;
+        let mut players = self
+            .world
+            .query_filtered::<&mut NoStdTransform, With<Player>>();
+        for mut transform in players.iter_mut(&mut self.world) {
+            transform.0.translation.x = x;
+            transform.0.translation.y = y;
+        }
+    }
+
+    /// Render the current state at a display size, like a board's render system.
+    pub fn render(&mut self, size: Size, zoom: Zoom) -> HostFrameBuffer {
+        let mut players = self.world.query_filtered::<&NoStdTransform, With<Player>>();
+        let mut npcs = self
+            .world
+            .query_filtered::<&NoStdTransform, With<NpcComponent>>();
+        let world = &self.world;
+        let camera = camera_position(
+            players.single(world).ok(),
+            world.resource::<PlayerPosition>(),
+        );
+        let npcs: Vec<Point> = npcs.iter(world).map(sprite_position).collect();
+        let texture_assets = world.resource::<TextureAssets>();
+
+        let mut frame = HostFrameBuffer::new(size);
+        let tile_cache = TileCache::new(texture_assets, zoom, &Direct);
+        let Ok(_summary) = render_frame_cached(
+            &mut frame,
+            &tile_cache,
+            &world.resource::<MazeResource>().maze,
+            camera,
+            npcs,
+            world.resource::<HudState>(),
+        );
+        frame
+    }
+}
+
+/// A board that exists only in memory: a `HostFrameBuffer` as display, a fixed seed,
+/// a clock that advances by `frame_time` after every frame, and tilt and touch input
+/// set by the caller. Vibrations are recorded instead of felt.
+pub struct VirtualBoard {
+    pub display: HostFrameBuffer,
+    pub seed: [u8; 32],
+    pub clock: Duration,
+    pub frame_time: Duration,
+    /// Returned by `read_tilt`; `None` behaves like a failed sensor read.
+    pub tilt: Option<Tilt>,
+    pub touch_panel: bool,
+    pub touch: Option<Point>,
+    pub vibrations: Vec<Duration>,
+    /// Shows the display as a 1-bit panel would, in black and white.
+    pub monochrome: Option<Monochrome>,
+}
+
+impl VirtualBoard {
+    pub fn new(size: Size, seed: [u8; 32]) -> Self {
+        Self {
+            display: HostFrameBuffer::new(size),
+            seed,
+            clock: Duration::ZERO,
+            frame_time: Duration::from_millis(33),
+            tilt: Some(Tilt::default()),
+            touch_panel: false,
+            touch: None,
+            vibrations: Vec::new(),
+            monochrome: None,
+        }
+    }
+
+    /// The board inside an app built with `spooky_core::board::build_app`.
+    pub fn of(app: &App) -> &Self {
+        &app.world().non_send_resource::<BoardResource<Self>>().0
+    }
+
+    pub fn of_mut(app: &mut App) -> &mut Self {
+        &mut app
+            .world_mut()
+            .non_send_resource_mut::<BoardResource<Self>>()
+            .into_inner()
+            .0
+    }
+}
+
+impl Board for VirtualBoard {
+    type Display = HostFrameBuffer;
+    type Palette = Option<Monochrome>;
+
+    fn display_size(&self) -> Size {
+        self.display.size()
+    }
+
+    fn display(&mut self) -> &mut Self::Display {
+        &mut self.display
+    }
+
+    fn palette(&self) -> Self::Palette {
+        self.monochrome
+    }
+
+    fn seed(&mut self) -> [u8; 32] {
+        self.seed
+    }
+
+    fn now(&self) -> Duration {
+        self.clock
+    }
+
+    fn read_tilt(&mut self) -> Option<Tilt> {
+        self.tilt
+    }
+
+    fn has_touch(&self) -> bool {
+        self.touch_panel
+    }
+
+    fn read_touch(&mut self) -> Option<Point> {
+        self.touch
+    }
+
+    fn vibrate(&mut self, duration: Duration) {
+        self.vibrations.push(duration);
+    }
+
+    fn idle(&mut self) {
+        self.clock += self.frame_time;
+    }
+}
@@ -0,0 +1,72 @@
+//! Golden-image tests of the embedded renderer, one per board display resolution.
+//!
+//! Each test renders a fixed seed and player position and compares the frame with
+//! `tests/golden/<name>.png`. Run with `UPDATE_GOLDEN=1` to (re)write the images after
+//! an intended change to the rendering; without it a missing image fails the test. The
+//! rendered frame of a failing test is saved next to the golden one as
+//! `<name>.actual.png`.
+
+use std::path::PathBuf;
+
+use embedded_graphics::prelude::Size;
+use spooky_core::render::zoom::Zoom;
+use spooky_core::resources::MazeResource;
+use spooky_host::{HostGame, read_png};
+
+const SEED: [u8; 32] = [42; 32];
+
+fn check_golden(name: &str, size: Size, zoom: Zoom) {
+    let mut game = HostGame::new(SEED);
+    // Tile (11, 10) of the playable area, where `setup` places the player, shifted by
+    // half a tile to catch sub-tile scrolling errors.
+    let maze = &game.world.resource::<MazeResource>().maze;
+    let (left, bottom, _right, _top) = maze.playable_bounds();
+    let (tile_width, tile_height) = (maze.tile_width as f32, maze.tile_height as f32);
+    game.set_player_position(
+        left as f32 + 11.0 * tile_width + tile_width / 2.0,
+        bottom as f32 + 10.0 * tile_height,
+    );
+    let frame = game.render(size, zoom);
+
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let golden = dir.join(format!("{name}.png"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(&dir).unwrap();
+        frame.save_png(&golden).unwrap();
+        eprintln!("wrote {}", golden.display());
+        return;
+    }
+    assert!(
+        golden.exists(),
+        "{} is missing; rerun with UPDATE_GOLDEN=1 to write it",
+        golden.display()
+    );
+
+    let (golden_size, golden_pixels) = read_png(&golden).unwrap();
+    let actual = dir.join(format!("{name}.actual.png"));
+    if golden_size != size || golden_pixels != frame.to_rgb888() {
+        frame.save_png(&actual).unwrap();
+        panic!(
+            "{name} differs from {}; see {} or rerun with UPDATE_GOLDEN=1",
+            golden.display(),
+            actual.display()
+        );
+    }
+}
+
+#[test]
+fn golden_320x240() {
+    // ESP32-S3-BOX-3, M5Stack Core2 and CoreS3.
+    check_golden("320x240", Size::new(320, 240), Zoom::Normal);
+}
+
+#[test]
+fn golden_130x129() {
+    // M5Stack Atom S3 and S3R, with their default zoom.
+    check_golden("130x129", Size::new(130, 129), Zoom::Half);
+}
+
+#[test]
+fn golden_128x128() {
+    check_golden("128x128", Size::new(128, 128), Zoom::Half);
+}
@@ -0,0 +1,117 @@
+//! Records a game on `VirtualBoard` and replays it on another one.
+
+use embedded_graphics::prelude::Size;
+use spooky_core::board::{GameConfig, build_app, update};
+use spooky_core::events::action::PlayerAction;
+use spooky_core::movement::MovementMode;
+use spooky_core::replay::{
+    Recorder, Recording, Replay, ReplayError, TickInput, add_recorder, add_replay,
+};
+use spooky_core::resources::MazeConfig;
+use spooky_core::seed_code::SeedCode;
+use spooky_core::systems::tilt_input::Tilt;
+use spooky_host::VirtualBoard;
+
+const SIZE: Size = Size::new(130, 129);
+
+fn run_frames(app: &mut bevy::app::App, frames: usize) {
+    for _ in 0..frames {
+        update::<VirtualBoard>(app);
+    }
+}
+
+/// A short game steered by tilting the board.
+fn record_game() -> Recording {
+    let config = GameConfig::default();
+    let mut app = build_app(VirtualBoard::new(SIZE, [0; 32]), config);
+    add_recorder(
+        &mut app,
+        Recording::new(
+            SeedCode(0x5b00c).to_seed(),
+            MazeConfig {
+                coins: 20,
+                npcs: 3,
+                ..MazeConfig::default()
+            },
+            MovementMode::Grid,
+            config.game_step,
+        ),
+    );
+    for (x, y) in [(0.5, 0.0), (0.0, 0.5), (-0.5, 0.0), (0.0, -0.5), (0.0, 0.0)] {
+        VirtualBoard::of_mut(&mut app).tilt = Some(Tilt { x, y });
+        run_frames(&mut app, 30);
+    }
+    app.world_mut()
+        .remove_resource::<Recorder>()
+        .expect("recorder")
+        .recording
+}
+
+/// Replays on a board without input and returns the finished replay.
+fn replay(recording: Recording) -> Replay {
+    let mut app = build_app(VirtualBoard::new(SIZE, [1; 32]), GameConfig::default());
+    add_replay(&mut app, recording);
+    for _ in 0..1000 {
+        update::<VirtualBoard>(&mut app);
+        if app.world().resource::<Replay>().is_finished() {
+            break;
+        }
+    }
+    app.world_mut().remove_resource::<Replay>().expect("replay")
+}
+
+#[test]
+fn replay_reaches_the_recorded_state() {
+    let recording = record_game();
+    assert!(recording.ticks.iter().any(|tick| !tick.moves.is_empty()));
+    let bytes = recording.encode();
+    assert_eq!(Recording::decode(&bytes).as_ref(), Ok(&recording));
+
+    let replay = replay(Recording::decode(&bytes).unwrap());
+    assert!(replay.is_finished());
+    assert_eq!(replay.final_hash(), recording.final_hash);
+    assert_eq!(replay.matches(), Some(true));
+}
+
+#[test]
+fn changed_input_diverges() {
+    // An extra teleport leaves the teleport uncharged at the end.
+    let mut recording = record_game();
+    recording.ticks[0].actions.push(PlayerAction::Teleport);
+    let replay = replay(recording);
+    assert_eq!(replay.matches(), Some(false));
+}
+
+#[test]
+fn idle_ticks_are_run_length_encoded() {
+    let mut recording = Recording::new(
+        SeedCode(42).to_seed(),
+        MazeConfig::default(),
+        MovementMode::Continuous { max_speed: 80.0 },
+        GameConfig::default().game_step,
+    );
+    recording.ticks = vec![TickInput::default(); 10_000];
+    recording.ticks.push(TickInput {
+        moves: vec![(-16, 16)],
+        analog: Some((0.25, -1.0)),
+        actions: Vec::new(),
+    });
+    recording.final_hash = Some(0x0123_4567_89ab_cdef);
+
+    let bytes = recording.encode();
+    assert!(bytes.len() < 64, "{} bytes", bytes.len());
+    assert_eq!(Recording::decode(&bytes), Ok(recording));
+
+    assert_eq!(
+        Recording::decode(&bytes[..bytes.len() - 1]),
+        Err(ReplayError::Truncated)
+    );
+    assert_eq!(
+        Recording::decode(b"SPKX\x01"),
+        Err(ReplayError::NotARecording)
+    );
+    assert_eq!(
+        Recording::decode(b"SPKR\x09"),
+        Err(ReplayError::UnsupportedVersion(9))
+    );
+}
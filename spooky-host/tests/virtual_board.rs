@@ -0,0 +1,128 @@
+//! Runs the shared board code path (`build_app` and `update`) on `VirtualBoard`.
+
+use bevy::prelude::With;
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::{OriginDimensions, Point, Size};
+use spooky_core::board::{BoardFrame, GameConfig, build_app, update};
+use spooky_core::components::{NpcComponent, Player};
+use spooky_core::render::zoom::Zoom;
+use spooky_core::render::{camera_position, render_frame, sprite_position};
+use spooky_core::resources::{MazeResource, PlayerPosition};
+use spooky_core::systems::hud::HudState;
+use spooky_core::systems::setup::{NoStdTransform, TextureAssets};
+use spooky_core::systems::tilt_input::Tilt;
+use spooky_host::{HostFrameBuffer, VirtualBoard};
+
+const SEED: [u8; 32] = [42; 32];
+
+fn run_frames(app: &mut bevy::app::App, frames: usize) {
+    for _ in 0..frames {
+        update::<VirtualBoard>(app);
+    }
+}
+
+/// The current state of the app drawn by the full, uncached `render_frame`, the way
+/// the renderer worked before the tile cache and the dirty regions.
+fn render_full(app: &mut bevy::app::App) -> HostFrameBuffer {
+    let mut frame = HostFrameBuffer::new(VirtualBoard::of(app).display.size());
+    let world = app.world_mut();
+    let mut players = world.query_filtered::<&NoStdTransform, With<Player>>();
+    let mut npcs = world.query_filtered::<&NoStdTransform, With<NpcComponent>>();
+    let world = &*world;
+    let camera = camera_position(
+        players.single(world).ok(),
+        world.resource::<PlayerPosition>(),
+    );
+    let npcs: Vec<Point> = npcs.iter(world).map(sprite_position).collect();
+    render_frame(
+        &mut frame,
+        &world.resource::<MazeResource>().maze,
+        world.resource::<TextureAssets>(),
+        camera,
+        Zoom::Normal,
+        npcs,
+        world.resource::<HudState>(),
+    )
+    .unwrap();
+    frame
+}
+
+#[test]
+fn dirty_cached_display_matches_full_render() {
+    let mut app = build_app(
+        VirtualBoard::new(Size::new(320, 240), SEED),
+        GameConfig::default(),
+    );
+
+    // Tilt the board around so that the ghost walks, the camera scrolls and the NPCs
+    // move; after every frame the display must hold exactly what a full render draws.
+    let tilts = [(0.5, 0.0), (0.0, 0.5), (-0.5, 0.0), (0.0, -0.5), (0.0, 0.0)];
+    for (frame, (x, y)) in tilts.into_iter().flat_map(|tilt| [tilt; 12]).enumerate() {
+        VirtualBoard::of_mut(&mut app).tilt = Some(Tilt { x, y });
+        run_frames(&mut app, 1);
+        let full = render_full(&mut app);
+        assert!(
+            VirtualBoard::of(&app).display.pixels == full.pixels,
+            "display differs from the full render in frame {frame}"
+        );
+    }
+}
+
+#[test]
+fn display_matches_rendered_frame() {
+    let mut app = build_app(
+        VirtualBoard::new(Size::new(320, 240), SEED),
+        GameConfig::default(),
+    );
+    run_frames(&mut app, 20);
+
+    // Only dirty regions are flushed after the first frame, yet the display must hold
+    // exactly the frame that was rendered last.
+    let frame = &app.world().resource::<BoardFrame<Rgb565>>().frame;
+    let board = VirtualBoard::of(&app);
+    assert!(board.display.pixels[..] == frame.data[..]);
+    assert!(
+        board
+            .display
+            .pixels
+            .iter()
+            .any(|pixel| *pixel != board.display.pixels[0])
+    );
+}
+
+#[test]
+fn tilt_moves_the_player() {
+    let mut app = build_app(
+        VirtualBoard::new(Size::new(130, 129), SEED),
+        GameConfig::default(),
+    );
+    run_frames(&mut app, 1);
+
+    let start = {
+        let position = app.world().resource::<PlayerPosition>();
+        (position.x, position.y)
+    };
+    let maze = &app.world().resource::<MazeResource>().maze;
+    let (step_x, step_y) = (maze.tile_width as f32, maze.tile_height as f32);
+    let (dx, dy) = [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)]
+        .into_iter()
+        .find(|(dx, dy)| {
+            !maze.check_wall_collision(
+                (start.0 + dx * step_x) as i32,
+                (start.1 + dy * step_y) as i32,
+            )
+        })
+        .expect("the start tile has an open neighbour");
+
+    // Hold the tilt for one game step (300 ms at 33 ms per frame), then lay the board flat.
+    VirtualBoard::of_mut(&mut app).tilt = Some(Tilt {
+        x: dx * 0.5,
+        y: dy * 0.5,
+    });
+    run_frames(&mut app, 10);
+    VirtualBoard::of_mut(&mut app).tilt = Some(Tilt::default());
+    run_frames(&mut app, 10);
+
+    let position = app.world().resource::<PlayerPosition>();
+    assert!((position.x - start.0) * dx > 0.0 || (position.y - start.1) * dy > 0.0);
+}
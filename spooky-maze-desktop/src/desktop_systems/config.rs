@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use spooky_core::key_bindings::KeyBindings;
+use std::fs;
+use std::path::PathBuf;
+
+const KEY_BINDINGS_FILE: &str = "key_bindings.cfg";
+
+/// Per-user configuration directory: `$XDG_CONFIG_HOME/spooky-maze`, `%APPDATA%\spooky-maze`
+/// or `~/.config/spooky-maze`, whichever is available first.
+fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("spooky-maze"));
+    }
+    if let Some(dir) = std::env::var_os("APPDATA") {
+        return Some(PathBuf::from(dir).join("spooky-maze"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("spooky-maze"))
+}
+
+/// Load the key map from the config file, falling back to the defaults when the
+/// file is missing or cannot be parsed.
+pub fn load_key_bindings() -> KeyBindings {
+    let Some(path) = config_dir().map(|dir| dir.join(KEY_BINDINGS_FILE)) else {
+        return KeyBindings::default();
+    };
+    match fs::read_to_string(&path) {
+        Ok(text) => match KeyBindings::from_config_str(&text) {
+            Ok(bindings) => {
+                info!("Loaded key bindings from {}", path.display());
+                bindings
+            }
+            Err(err) => {
+                warn!("Ignoring {}: {}", path.display(), err);
+                KeyBindings::default()
+            }
+        },
+        Err(_) => KeyBindings::default(),
+    }
+}
+
+/// Write the key map to the config file.
+pub fn save_key_bindings(bindings: &KeyBindings) {
+    let Some(dir) = config_dir() else {
+        warn!("No config directory available, key bindings are not saved");
+        return;
+    };
+    let path = dir.join(KEY_BINDINGS_FILE);
+    let result =
+        fs::create_dir_all(&dir).and_then(|_| fs::write(&path, bindings.to_config_string()));
+    if let Err(err) = result {
+        warn!("Failed to save key bindings to {}: {}", path.display(), err);
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::prelude::*;
+use spooky_core::events::action::{PlayerAction, PlayerActionMessage};
+use spooky_core::events::player::PlayerAnalogInputMessage;
+
+use crate::desktop_systems::player_input::toggle_pause;
+
+/// Gamepad tuning shared by all connected pads.
+#[derive(Resource)]
+pub struct GamepadConfig {
+    /// Left stick deflection below this value is ignored.
+    pub dead_zone: f32,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self { dead_zone: 0.35 }
+    }
+}
+
+/// Logs gamepads as they are plugged in or removed. Bevy spawns and despawns the
+/// `Gamepad` entities itself, so the input systems simply see whatever is connected.
+pub fn log_gamepad_connections(mut events: MessageReader<GamepadConnectionEvent>) {
+    for event in events.read() {
+        match &event.connection {
+            GamepadConnection::Connected { name, .. } => {
+                info!("Gamepad {} connected: {}", event.gamepad, name);
+            }
+            GamepadConnection::Disconnected => {
+                info!("Gamepad {} disconnected", event.gamepad);
+            }
+        }
+    }
+}
+
+/// Direction of the D-pad and the left stick of the first gamepad with active input,
+/// in unit steps (positive dy is up). Two pads never add up to a double step.
+pub fn gamepad_direction(gamepads: &Query<&Gamepad>, config: &GamepadConfig) -> (i32, i32) {
+    for gamepad in gamepads.iter() {
+        let stick = gamepad.left_stick();
+        let up = gamepad.pressed(GamepadButton::DPadUp) || stick.y > config.dead_zone;
+        let down = gamepad.pressed(GamepadButton::DPadDown) || stick.y < -config.dead_zone;
+        let right = gamepad.pressed(GamepadButton::DPadRight) || stick.x > config.dead_zone;
+        let left = gamepad.pressed(GamepadButton::DPadLeft) || stick.x < -config.dead_zone;
+
+        let dx = right as i32 - left as i32;
+        let dy = up as i32 - down as i32;
+        if dx != 0 || dy != 0 {
+            return (dx, dy);
+        }
+    }
+    (0, 0)
+}
+
+/// Reports the left stick (or the D-pad as full deflection) for continuous movement
+/// mode. The dead zone is cut off and the remaining range rescaled to 0..1, so slight
+/// tilts give slow movement. Only the first pad with active input is reported.
+pub fn dispatch_gamepad_analog_input(
+    gamepads: Query<&Gamepad>,
+    config: Res<GamepadConfig>,
+    mut event_writer: MessageWriter<PlayerAnalogInputMessage>,
+) {
+    for gamepad in gamepads.iter() {
+        let mut input = gamepad.left_stick();
+        let length = input.length();
+        input = if length > config.dead_zone {
+            input / length * ((length - config.dead_zone) / (1.0 - config.dead_zone)).min(1.0)
+        } else {
+            Vec2::ZERO
+        };
+        if input == Vec2::ZERO {
+            let dpad = [
+                (GamepadButton::DPadUp, Vec2::Y),
+                (GamepadButton::DPadDown, Vec2::NEG_Y),
+                (GamepadButton::DPadRight, Vec2::X),
+                (GamepadButton::DPadLeft, Vec2::NEG_X),
+            ];
+            for (button, direction) in dpad {
+                if gamepad.pressed(button) {
+                    input += direction;
+                }
+            }
+            input = input.normalize_or_zero();
+        }
+        if input != Vec2::ZERO {
+            event_writer.write(PlayerAnalogInputMessage {
+                x: input.x,
+                y: input.y,
+            });
+            return;
+        }
+    }
+}
+
+/// Maps the face buttons: South teleports, East places dynamite and
+/// North (or Start) toggles pause by pausing the virtual clock that drives `FixedUpdate`.
+/// Runs in `Update` so that short presses are never missed between fixed ticks.
+pub fn dispatch_gamepad_actions(
+    gamepads: Query<&Gamepad>,
+    mut action_writer: MessageWriter<PlayerActionMessage>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    for gamepad in gamepads.iter() {
+        if gamepad.just_pressed(GamepadButton::South) {
+            action_writer.write(PlayerActionMessage {
+                action: PlayerAction::Teleport,
+            });
+        }
+        if gamepad.just_pressed(GamepadButton::East) {
+            action_writer.write(PlayerActionMessage {
+                action: PlayerAction::PlaceDynamite,
+            });
+        }
+        if gamepad.just_pressed(GamepadButton::North) || gamepad.just_pressed(GamepadButton::Start)
+        {
+            toggle_pause(&mut time);
+        }
+    }
+}
@@ -1,45 +1,56 @@
 // desktop_systems/hud.rs
 use bevy::prelude::*;
+use spooky_core::hud_layout::{HudLayout, HudWidget};
 use spooky_core::systems::hud::HudState;
 
-/// Marker component for our HUD text node.
+/// Marker component for a HUD text node, with the widget it shows.
 #[derive(Component)]
-pub struct HudText;
+pub struct HudText(pub HudWidget);
 
-pub fn setup_hud(mut commands: Commands) {
+/// Spawns one text node per widget of the desktop HUD layout.
+pub fn setup_hud(mut commands: Commands, hud_state: Res<HudState>) {
     // Spawn the HUD root node.
     commands
         .spawn((
             Node {
                 position_type: PositionType::Absolute,
-                justify_content: JustifyContent::FlexStart,
-                align_items: AlignItems::FlexStart,
-                padding: UiRect::all(Val::Px(5.0)),
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
                 ..default()
             },
             Name::new("HUD Root"),
         ))
         .with_children(|parent| {
-            parent.spawn((
-                Text("Coins: 0  Teleport: 100  Walker: 0  Dynamite: 0".to_string()),
-                HudText,
-                Name::new("HUD Text"),
-            ));
+            for layout in HudLayout::DESKTOP.widgets {
+                parent.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(layout.area.top_left.x as f32),
+                        top: Val::Px(layout.area.top_left.y as f32),
+                        width: Val::Px(layout.area.size.width as f32),
+                        height: Val::Px(layout.area.size.height as f32),
+                        ..default()
+                    },
+                    Text(widget_text(layout.widget, &hud_state)),
+                    HudText(layout.widget),
+                    Name::new(format!("HUD {}", layout.widget.label())),
+                ));
+            }
         });
 }
 
-/// Updates the HUD text based on the current HudState resource.
-pub fn update_hud(hud_state: Res<HudState>, mut query: Query<&mut Text, With<HudText>>) {
+/// Updates the HUD texts based on the current HudState resource.
+pub fn update_hud(hud_state: Res<HudState>, mut query: Query<(&mut Text, &HudText)>) {
     if hud_state.is_changed() {
-        for mut text in query.iter_mut() {
-            // Overwrite the text with the new HUD values.
-            *text = Text(format!(
-                "Coins: {}  Teleport: {}  Walker: {}  Dynamite: {}",
-                hud_state.coins_left,
-                hud_state.teleport_countdown,
-                hud_state.walker_timer,
-                hud_state.dynamites,
-            ));
+        for (mut text, hud_text) in query.iter_mut() {
+            // Overwrite the text with the new HUD value.
+            *text = Text(widget_text(hud_text.0, &hud_state));
         }
     }
 }
+
+fn widget_text(widget: HudWidget, hud_state: &HudState) -> String {
+    let mut text = format!("{}: ", widget.label());
+    let _ = widget.write_value(&mut text, hud_state);
+    text
+}
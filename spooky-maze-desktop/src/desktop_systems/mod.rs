@@ -1,2 +1,6 @@
+pub mod config;
+pub mod gamepad_input;
 pub mod hud;
 pub mod player_input;
+pub mod rebind_screen;
+pub mod recording;
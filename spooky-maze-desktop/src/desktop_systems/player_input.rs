@@ -1,31 +1,134 @@
 use bevy::prelude::*;
-use spooky_core::events::player::PlayerInputEvent;
+use spooky_core::events::action::{PlayerAction, PlayerActionMessage};
+use spooky_core::events::player::{PlayerAnalogInputMessage, PlayerInputMessage};
+use spooky_core::input_buffer::{InputBuffer, InputRepeatConfig};
+use spooky_core::key_bindings::{InputAction, KeyBindings};
+use spooky_core::resources::MazeResource;
 
-/// Reads keyboard input (arrow keys) and sends a PlayerInputEvent continuously
-/// while keys are held down. A positive dx moves right; a positive dy moves up.
-/// The step is defined as one tile.
-pub fn dispatch_keyboard_input(
+use crate::desktop_systems::gamepad_input::{GamepadConfig, gamepad_direction};
+use crate::desktop_systems::rebind_screen::RebindScreen;
+
+/// Movement actions and the one-tile direction they stand for.
+const MOVE_ACTIONS: [(InputAction, i32, i32); 4] = [
+    (InputAction::MoveUp, 0, 1),
+    (InputAction::MoveDown, 0, -1),
+    (InputAction::MoveLeft, -1, 0),
+    (InputAction::MoveRight, 1, 0),
+];
+
+/// Records the movement keys from the `KeyBindings` map and the gamepads into the
+/// `InputBuffer` every frame, so that quick taps between two fixed ticks are not lost
+/// and a key and a stick held together still give only one move per tick.
+#[allow(clippy::too_many_arguments)]
+pub fn record_movement_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    gamepads: Query<&Gamepad>,
+    gamepad_config: Res<GamepadConfig>,
+    rebind_screen: Res<RebindScreen>,
+    config: Res<InputRepeatConfig>,
+    time: Res<Time<Virtual>>,
+    mut buffer: ResMut<InputBuffer>,
+    mut last_pad: Local<(i32, i32)>,
+) {
+    if rebind_screen.open {
+        buffer.clear();
+        return;
+    }
+    let mut held_x = 0;
+    let mut held_y = 0;
+    for (action, dx, dy) in MOVE_ACTIONS {
+        if key_bindings.just_pressed(&keyboard_input, action) {
+            buffer.press(dx, dy);
+        }
+        if key_bindings.pressed(&keyboard_input, action) {
+            held_x += dx;
+            held_y += dy;
+        }
+    }
+    // The stick has no press events, so a change of the pad's direction is the tap.
+    let pad = gamepad_direction(&gamepads, &gamepad_config);
+    if pad != *last_pad {
+        buffer.press(pad.0, pad.1);
+        *last_pad = pad;
+    }
+    buffer.set_held(held_x + pad.0, held_y + pad.1, time.elapsed_secs(), &config);
+}
+
+/// Replays the buffered movement on the fixed tick: one `PlayerInputMessage` per tick,
+/// taps first in the order they happened, then key repeat for held keys and pads.
+/// A positive dx moves right; a positive dy moves up. The step is one tile.
+pub fn dispatch_movement_input(
+    config: Res<InputRepeatConfig>,
+    time: Res<Time<Virtual>>,
+    maze_res: Res<MazeResource>,
+    mut buffer: ResMut<InputBuffer>,
+    mut event_writer: MessageWriter<PlayerInputMessage>,
+) {
+    if let Some((dx, dy)) = buffer.next_move(time.elapsed_secs(), &config) {
+        event_writer.write(PlayerInputMessage {
+            dx: dx as f32 * maze_res.maze.tile_width as f32,
+            dy: dy as f32 * maze_res.maze.tile_height as f32,
+        });
+    }
+}
+
+/// Reports the held movement keys as full-deflection analog input for continuous
+/// movement mode. Sent every tick, so releasing the keys stops the player.
+pub fn dispatch_keyboard_analog_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut event_writer: EventWriter<PlayerInputEvent>,
+    key_bindings: Res<KeyBindings>,
+    rebind_screen: Res<RebindScreen>,
+    mut event_writer: MessageWriter<PlayerAnalogInputMessage>,
 ) {
-    let mut dx = 0.0;
-    let mut dy = 0.0;
-    let step = 16.0; // adjust to your tile size
+    let mut direction = Vec2::ZERO;
+    if !rebind_screen.open {
+        for (action, dx, dy) in MOVE_ACTIONS {
+            if key_bindings.pressed(&keyboard_input, action) {
+                direction += Vec2::new(dx as f32, dy as f32);
+            }
+        }
+    }
+    let direction = direction.normalize_or_zero();
+    event_writer.write(PlayerAnalogInputMessage {
+        x: direction.x,
+        y: direction.y,
+    });
+}
 
-    if keyboard_input.pressed(KeyCode::ArrowUp) {
-        dy += step;
+/// Reads the teleport, dynamite and pause keys. Runs in `Update` so that short
+/// presses are not lost between fixed ticks, and stays quiet while the rebinding
+/// screen owns the keyboard.
+pub fn dispatch_keyboard_actions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    rebind_screen: Res<RebindScreen>,
+    mut action_writer: MessageWriter<PlayerActionMessage>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if rebind_screen.open {
+        return;
     }
-    if keyboard_input.pressed(KeyCode::ArrowDown) {
-        dy -= step;
+    if key_bindings.just_pressed(&keyboard_input, InputAction::Teleport) {
+        action_writer.write(PlayerActionMessage {
+            action: PlayerAction::Teleport,
+        });
     }
-    if keyboard_input.pressed(KeyCode::ArrowRight) {
-        dx += step;
+    if key_bindings.just_pressed(&keyboard_input, InputAction::PlaceDynamite) {
+        action_writer.write(PlayerActionMessage {
+            action: PlayerAction::PlaceDynamite,
+        });
     }
-    if keyboard_input.pressed(KeyCode::ArrowLeft) {
-        dx -= step;
+    if key_bindings.just_pressed(&keyboard_input, InputAction::Pause) {
+        toggle_pause(&mut time);
     }
+}
 
-    if dx != 0.0 || dy != 0.0 {
-        event_writer.write(PlayerInputEvent { dx, dy });
+/// Pause or resume the virtual clock that drives `FixedUpdate`.
+pub fn toggle_pause(time: &mut Time<Virtual>) {
+    if time.is_paused() {
+        time.unpause();
+    } else {
+        time.pause();
     }
 }
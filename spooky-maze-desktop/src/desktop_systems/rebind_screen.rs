@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+use spooky_core::key_bindings::{InputAction, KeyBindings, SLOTS_PER_ACTION, key_name};
+
+use crate::desktop_systems::config::save_key_bindings;
+
+/// Key that opens and closes the rebinding screen. It cannot be rebound itself.
+const TOGGLE_KEY: KeyCode = KeyCode::F1;
+
+/// State of the rebinding screen. Every action has one row per binding slot.
+#[derive(Resource, Default)]
+pub struct RebindScreen {
+    pub open: bool,
+    selected: usize,
+    capturing: bool,
+    message: String,
+    /// Whether opening the screen paused the game clock, so closing it only resumes a
+    /// game the player had not paused already.
+    paused_clock: bool,
+}
+
+impl RebindScreen {
+    fn selection(&self) -> (InputAction, usize) {
+        (
+            InputAction::ALL[self.selected / SLOTS_PER_ACTION],
+            self.selected % SLOTS_PER_ACTION,
+        )
+    }
+}
+
+/// Marker component for the rebinding screen root node.
+#[derive(Component)]
+pub struct RebindScreenRoot;
+
+/// Marker component for the rebinding screen text.
+#[derive(Component)]
+pub struct RebindScreenText;
+
+pub fn setup_rebind_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(40.0),
+                left: Val::Px(20.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            Visibility::Hidden,
+            RebindScreenRoot,
+            Name::new("Rebind Screen"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text(String::new()),
+                RebindScreenText,
+                Name::new("Rebind Text"),
+            ));
+        });
+}
+
+/// Drives the rebinding screen: F1 toggles it, arrows select a slot, Enter waits for
+/// the next key, Backspace clears the slot and R restores the defaults. A key that is
+/// already used by another slot is rejected and the conflict is reported. The game clock
+/// is paused while the screen is open; a game paused before stays paused on close.
+pub fn handle_rebind_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut screen: ResMut<RebindScreen>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if !screen.capturing && keyboard_input.just_pressed(TOGGLE_KEY) {
+        screen.open = !screen.open;
+        screen.message.clear();
+        if screen.open {
+            screen.paused_clock = !time.is_paused();
+            time.pause();
+        } else if screen.paused_clock {
+            time.unpause();
+        }
+        return;
+    }
+    if !screen.open {
+        return;
+    }
+
+    let (action, slot) = screen.selection();
+
+    if screen.capturing {
+        if keyboard_input.just_pressed(KeyCode::Escape) {
+            screen.capturing = false;
+            screen.message = "Cancelled".to_string();
+            return;
+        }
+        let Some(key) = keyboard_input
+            .get_just_pressed()
+            .copied()
+            .find(|key| *key != TOGGLE_KEY && key_name(*key).is_some())
+        else {
+            return;
+        };
+        screen.capturing = false;
+        screen.message = match key_bindings.rebind(action, slot, key) {
+            Ok(()) => {
+                save_key_bindings(&key_bindings);
+                format!(
+                    "{} bound to {}",
+                    action.label(),
+                    key_name(key).unwrap_or("?")
+                )
+            }
+            Err(conflict) => format!("Conflict: {}", conflict),
+        };
+        return;
+    }
+
+    let rows = InputAction::ALL.len() * SLOTS_PER_ACTION;
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        screen.selected = (screen.selected + 1) % rows;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        screen.selected = (screen.selected + rows - 1) % rows;
+    }
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        screen.capturing = true;
+        screen.message = "Press a key (Escape cancels)".to_string();
+    }
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        key_bindings.clear(action, slot);
+        save_key_bindings(&key_bindings);
+        screen.message = format!("{} cleared", action.label());
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        *key_bindings = KeyBindings::default();
+        save_key_bindings(&key_bindings);
+        screen.message = "Defaults restored".to_string();
+    }
+}
+
+/// Shows or hides the rebinding screen and refreshes its text.
+pub fn update_rebind_screen(
+    screen: Res<RebindScreen>,
+    key_bindings: Res<KeyBindings>,
+    mut root_query: Query<&mut Visibility, With<RebindScreenRoot>>,
+    mut text_query: Query<&mut Text, With<RebindScreenText>>,
+) {
+    if !screen.is_changed() && !key_bindings.is_changed() {
+        return;
+    }
+    for mut visibility in root_query.iter_mut() {
+        *visibility = if screen.open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+
+    let mut content = String::from(
+        "Controls (F1 to close)\nUp/Down: select  Enter: rebind  Backspace: clear  R: defaults\n\n",
+    );
+    for (row, (action, slot)) in InputAction::ALL
+        .into_iter()
+        .flat_map(|action| (0..SLOTS_PER_ACTION).map(move |slot| (action, slot)))
+        .enumerate()
+    {
+        let marker = if row == screen.selected { ">" } else { " " };
+        let key = key_bindings
+            .key(action, slot)
+            .and_then(key_name)
+            .unwrap_or("-");
+        let slot_name = if slot == 0 { "primary" } else { "secondary" };
+        content.push_str(&format!(
+            "{} {:<15} {:<10} {}\n",
+            marker,
+            action.label(),
+            slot_name,
+            key
+        ));
+    }
+    content.push('\n');
+    content.push_str(&screen.message);
+
+    for mut text in text_query.iter_mut() {
+        *text = Text(content.clone());
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use spooky_core::replay::{Recorder, Recording};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File the recording of `--record` is saved to.
+#[derive(Resource)]
+pub struct RecordPath(pub PathBuf);
+
+/// Read a recording for `--replay`.
+pub fn load_recording(path: &Path) -> Result<Recording, String> {
+    let bytes = fs::read(path).map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+    Recording::decode(&bytes).map_err(|err| format!("cannot replay {}: {}", path.display(), err))
+}
+
+/// Saves the recording when the game exits, also on Ctrl-C.
+pub fn save_recording(
+    mut exit_events: MessageReader<AppExit>,
+    recorder: Res<Recorder>,
+    path: Res<RecordPath>,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+    let recording = &recorder.recording;
+    match fs::write(&path.0, recording.encode()) {
+        Ok(()) => info!(
+            "Saved {} ticks to {}, state hash {:016x}",
+            recording.ticks.len(),
+            path.0.display(),
+            recording.final_hash.unwrap_or_default()
+        ),
+        Err(err) => error!("Cannot save the recording to {}: {}", path.0.display(), err),
+    }
+}
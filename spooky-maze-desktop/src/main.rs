@@ -1,37 +1,97 @@
 mod desktop_systems;
+mod options;
 
+use crate::desktop_systems::config::load_key_bindings;
+use crate::desktop_systems::gamepad_input::{self, GamepadConfig};
 use crate::desktop_systems::hud::{setup_hud, update_hud};
 use crate::desktop_systems::player_input;
+use crate::desktop_systems::rebind_screen::{self, RebindScreen};
+use crate::desktop_systems::recording::{RecordPath, load_recording, save_recording};
+use crate::options::{Command, Options, USAGE};
+use bevy::app::ScheduleRunnerPlugin;
 use bevy::prelude::*;
+use bevy::render::RenderPlugin;
+use bevy::render::settings::{RenderCreation, WgpuSettings};
+use bevy::window::{ExitCondition, MonitorSelection, WindowMode};
+use bevy::winit::WinitPlugin;
 use rand::RngCore;
-use spooky_core::events::npc::NpcCollisionEvent;
-use spooky_core::events::player::PlayerInputEvent;
-use spooky_core::events::walker::WalkerCollisionEvent;
-use spooky_core::events::{coin::CoinCollisionEvent, dynamite::DynamiteCollisionEvent};
+use spooky_core::events::action::PlayerActionMessage;
+use spooky_core::events::npc::NpcCollisionMessage;
+use spooky_core::events::player::{PlayerAnalogInputMessage, PlayerInputMessage};
+use spooky_core::events::walker::WalkerCollisionMessage;
+use spooky_core::events::{coin::CoinCollisionMessage, dynamite::DynamiteCollisionMessage};
+use spooky_core::input_buffer::{InputBuffer, InputRepeatConfig};
+use spooky_core::replay::{Recording, add_recorder, add_replay};
 use spooky_core::resources::MazeSeed;
+use spooky_core::seed_code::SeedCode;
+use spooky_core::systems::TickSet;
 use spooky_core::systems::hud::HudState;
+use spooky_core::systems::motion::StepInterpolation;
 use spooky_core::{systems, systems::collisions};
+use std::time::Duration;
 
 fn main() {
+    let random_seed = SeedCode::random(rand::rng().next_u64());
+    let options = match options::parse_args(std::env::args().skip(1), random_seed) {
+        Ok(Command::Play(options)) => options,
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    let replay = options.replay.as_deref().map(|path| {
+        load_recording(path).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(1);
+        })
+    });
+    let game_step = Duration::from_secs_f64(1.0 / options.tick_hz);
+    match &replay {
+        Some(recording) => println!("Replaying {} ticks", recording.ticks.len()),
+        None => println!("Maze seed: {}", options.seed),
+    }
+
     let mut app = App::new();
-    app.add_plugins((DefaultPlugins,))
-        .insert_resource(MazeSeed(Some({
-            let mut seed = [0u8; 32];
-            rand::rng().fill_bytes(seed.as_mut());
-            seed
-        })))
-        .add_systems(Startup, (systems::setup::setup, setup_hud))
-        .insert_resource(Time::<Fixed>::from_hz(10.0))
-        .add_event::<PlayerInputEvent>()
-        .add_event::<CoinCollisionEvent>()
-        .add_event::<DynamiteCollisionEvent>()
-        .add_event::<WalkerCollisionEvent>()
-        .add_event::<NpcCollisionEvent>()
+    add_default_plugins(&mut app, &options);
+    app.insert_resource(MazeSeed(Some(options.seed.to_seed())))
+        .insert_resource(options.maze)
+        .add_systems(
+            Startup,
+            (
+                systems::setup::setup,
+                setup_hud,
+                rebind_screen::setup_rebind_screen,
+            ),
+        )
+        .insert_resource(Time::<Fixed>::from_duration(game_step))
+        .add_message::<PlayerInputMessage>()
+        .add_message::<PlayerAnalogInputMessage>()
+        .add_message::<PlayerActionMessage>()
+        .add_message::<CoinCollisionMessage>()
+        .add_message::<DynamiteCollisionMessage>()
+        .add_message::<WalkerCollisionMessage>()
+        .add_message::<NpcCollisionMessage>()
         .insert_resource(HudState::default())
+        .insert_resource(options.movement_mode)
+        .init_resource::<GamepadConfig>()
+        .insert_resource(load_key_bindings())
+        .init_resource::<RebindScreen>()
+        .init_resource::<InputRepeatConfig>()
+        .init_resource::<InputBuffer>()
+        .configure_sets(
+            FixedUpdate,
+            (TickSet::Input, TickSet::Record, TickSet::Logic).chain(),
+        )
         .add_systems(
             FixedUpdate,
             (
                 systems::process_player_input::process_player_input,
+                systems::continuous_movement::apply_continuous_movement,
+                systems::player_action::handle_player_action,
                 collisions::coin::detect_coin_collision,
                 collisions::coin::remove_coin_on_collision,
                 collisions::dynamite::handle_dynamite_collision,
@@ -42,9 +102,97 @@ fn main() {
                 systems::dynamite_logic::handle_dynamite_collision,
                 systems::npc_logic::update_npc_movement,
                 systems::game_logic::update_game,
-                player_input::dispatch_keyboard_input,
+            )
+                .in_set(TickSet::Logic),
+        )
+        .add_systems(
+            Update,
+            (
+                update_hud,
+                systems::motion::interpolate_motion,
+                gamepad_input::log_gamepad_connections,
+                rebind_screen::handle_rebind_input,
+                rebind_screen::update_rebind_screen,
             ),
+        );
+
+    match replay {
+        // The recording brings its own seed, maze and timing; live input is left out.
+        Some(recording) => {
+            app.insert_resource(StepInterpolation {
+                duration: recording.game_step.as_secs_f32(),
+            });
+            add_replay(&mut app, recording);
+        }
+        None => {
+            app.insert_resource(StepInterpolation {
+                duration: game_step.as_secs_f32(),
+            })
+            .add_systems(
+                FixedUpdate,
+                (
+                    player_input::dispatch_movement_input,
+                    player_input::dispatch_keyboard_analog_input,
+                    gamepad_input::dispatch_gamepad_analog_input,
+                )
+                    .in_set(TickSet::Input),
+            )
+            .add_systems(
+                Update,
+                (
+                    player_input::record_movement_input,
+                    gamepad_input::dispatch_gamepad_actions,
+                    player_input::dispatch_keyboard_actions,
+                ),
+            );
+        }
+    }
+    if let Some(path) = options.record {
+        let recording = Recording::new(
+            options.seed.to_seed(),
+            options.maze,
+            options.movement_mode,
+            game_step,
+        );
+        add_recorder(&mut app, recording);
+        app.insert_resource(RecordPath(path))
+            .add_systems(Last, save_recording);
+    }
+    app.run();
+}
+
+/// Bevy's default plugins, with a fullscreen window for `--fullscreen`. `--headless`
+/// runs the schedule in a plain loop without a window, input devices or a GPU.
+fn add_default_plugins(app: &mut App, options: &Options) {
+    if options.headless {
+        app.add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .set(RenderPlugin {
+                    render_creation: RenderCreation::Automatic(WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .disable::<WinitPlugin>(),
         )
-        .add_systems(Update, (update_hud,))
-        .run();
+        .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )));
+    } else if options.fullscreen {
+        app.add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                mode: WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+                ..default()
+            }),
+            ..default()
+        }));
+    } else {
+        app.add_plugins(DefaultPlugins);
+    }
 }
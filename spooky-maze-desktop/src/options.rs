@@ -0,0 +1,342 @@
+//! Command line of the desktop game.
+
+use spooky_core::maze::{Maze, MazeAlgorithm};
+use spooky_core::movement::{DEFAULT_CONTINUOUS_SPEED, MovementMode};
+use spooky_core::resources::MazeConfig;
+use spooky_core::seed_code::SeedCode;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: spooky-maze-desktop [options]
+
+  --seed <code>          maze seed code (default: random)
+  --width <tiles>        maze width, an even number from 14 to 64 (default 64)
+  --height <tiles>       maze height, an even number from 14 to 64 (default 64)
+  --generator <name>     `backtracking` or `prim` (default backtracking)
+  --static-maze          play the fixed 64x64 maze instead of a generated one
+  --coins <n>            coins at the start, 1 to 100 (default 100)
+  --npcs <n>             NPCs, 0 to 5 (default 5)
+  --tick-hz <hz>         game steps per second, 1 to 60 (default 10)
+  --continuous           free movement instead of tile steps
+  --fullscreen           borderless fullscreen window
+  --headless             run the game without a window or rendering
+  --record <file>        save the seed, settings and input of the game on exit
+  --replay <file>        watch a recorded game; its seed and settings replace
+                         the options above
+  -h, --help             show this help";
+
+/// Game steps per second without `--tick-hz`.
+pub const DEFAULT_TICK_HZ: f64 = 10.0;
+
+/// Width and height of the static maze in tiles.
+const STATIC_TILES: u32 = 64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    pub seed: SeedCode,
+    pub maze: MazeConfig,
+    pub tick_hz: f64,
+    pub movement_mode: MovementMode,
+    pub fullscreen: bool,
+    pub headless: bool,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+}
+
+/// What the command line asks for.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Play(Options),
+    Help,
+}
+
+/// Parse the arguments after the program name. `random_seed` is used without
+/// `--seed`.
+pub fn parse_args(
+    args: impl IntoIterator<Item = String>,
+    random_seed: SeedCode,
+) -> Result<Command, String> {
+    let mut seed = random_seed;
+    let mut width = None;
+    let mut height = None;
+    let mut generator = None;
+    let mut static_maze = false;
+    let mut coins = Maze::MAX_COINS;
+    let mut npcs = Maze::MAX_NPCS as u32;
+    let mut tick_hz = DEFAULT_TICK_HZ;
+    let mut continuous = false;
+    let mut fullscreen = false;
+    let mut headless = false;
+    let mut record = None;
+    let mut replay = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--seed" => {
+                seed = value()?
+                    .parse()
+                    .map_err(|error| format!("invalid --seed: {error}"))?
+            }
+            "--width" => width = Some(tiles(&arg, &value()?)?),
+            "--height" => height = Some(tiles(&arg, &value()?)?),
+            "--generator" => {
+                let name = value()?;
+                generator = Some(
+                    name.parse::<MazeAlgorithm>()
+                        .map_err(|error| format!("invalid --generator `{name}`: {error}"))?,
+                );
+            }
+            "--static-maze" => static_maze = true,
+            "--coins" => coins = number(&arg, &value()?, 1, Maze::MAX_COINS)?,
+            "--npcs" => npcs = number(&arg, &value()?, 0, Maze::MAX_NPCS as u32)?,
+            "--tick-hz" => {
+                tick_hz = value()?
+                    .parse()
+                    .ok()
+                    .filter(|hz| (1.0..=60.0).contains(hz))
+                    .ok_or("--tick-hz must be a number from 1 to 60")?
+            }
+            "--continuous" => continuous = true,
+            "--fullscreen" => fullscreen = true,
+            "--headless" => headless = true,
+            "--record" => record = Some(PathBuf::from(value()?)),
+            "--replay" => replay = Some(PathBuf::from(value()?)),
+            "--help" | "-h" => return Ok(Command::Help),
+            _ => return Err(format!("unknown argument `{arg}`")),
+        }
+    }
+
+    // Builds without the `dynamic_maze` feature only have the static maze.
+    let static_layout = static_maze || !cfg!(feature = "dynamic_maze");
+    if static_layout {
+        if width.unwrap_or(STATIC_TILES) != STATIC_TILES
+            || height.unwrap_or(STATIC_TILES) != STATIC_TILES
+        {
+            return Err(format!(
+                "the static maze is {STATIC_TILES}x{STATIC_TILES} tiles"
+            ));
+        }
+        if generator.is_some() {
+            return Err("--generator needs a generated maze, not the static one".into());
+        }
+    }
+    if fullscreen && headless {
+        return Err("--fullscreen and --headless exclude each other".into());
+    }
+    if record.is_some() && replay.is_some() {
+        return Err("--record and --replay exclude each other".into());
+    }
+
+    let fields = |tiles: Option<u32>| tiles.map_or(MazeConfig::MAX_SIZE, |tiles| tiles / 2 - 1);
+    Ok(Command::Play(Options {
+        seed,
+        maze: MazeConfig {
+            width: fields(width),
+            height: fields(height),
+            algorithm: generator.unwrap_or_default(),
+            static_layout,
+            coins,
+            npcs,
+        },
+        tick_hz,
+        movement_mode: if continuous {
+            MovementMode::Continuous {
+                max_speed: DEFAULT_CONTINUOUS_SPEED,
+            }
+        } else {
+            MovementMode::Grid
+        },
+        fullscreen,
+        headless,
+        record,
+        replay,
+    }))
+}
+
+/// A maze side in tiles: even, so that it holds whole fields, and from the smallest
+/// maze to the 64 tiles of the maze data.
+fn tiles(arg: &str, value: &str) -> Result<u32, String> {
+    let min = MazeConfig::tiles(MazeConfig::MIN_SIZE);
+    let max = MazeConfig::tiles(MazeConfig::MAX_SIZE);
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|tiles| tiles % 2 == 0 && (min..=max).contains(tiles))
+        .ok_or_else(|| format!("{arg} must be an even number from {min} to {max}"))
+}
+
+fn number(arg: &str, value: &str, min: u32, max: u32) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|number| (min..=max).contains(number))
+        .ok_or_else(|| format!("{arg} must be a number from {min} to {max}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: SeedCode = SeedCode(0x2a);
+
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(String::from), SEED)
+    }
+
+    fn options(args: &str) -> Options {
+        match parse(args) {
+            Ok(Command::Play(options)) => options,
+            other => panic!("`{args}` parsed as {other:?}"),
+        }
+    }
+
+    fn error(args: &str) -> String {
+        parse(args).expect_err(args)
+    }
+
+    #[test]
+    fn defaults_without_arguments() {
+        let options = options("");
+        assert_eq!(options.seed, SEED);
+        assert_eq!(options.maze.width, MazeConfig::MAX_SIZE);
+        assert_eq!(options.maze.height, MazeConfig::MAX_SIZE);
+        assert_eq!(options.maze.algorithm, MazeAlgorithm::Backtracking);
+        assert_eq!(options.maze.static_layout, !cfg!(feature = "dynamic_maze"));
+        assert_eq!(options.maze.coins, Maze::MAX_COINS);
+        assert_eq!(options.maze.npcs, Maze::MAX_NPCS as u32);
+        assert_eq!(options.tick_hz, DEFAULT_TICK_HZ);
+        assert_eq!(options.movement_mode, MovementMode::Grid);
+        assert!(!options.fullscreen && !options.headless);
+        assert_eq!((options.record, options.replay), (None, None));
+    }
+
+    #[cfg(feature = "dynamic_maze")]
+    #[test]
+    fn accepts_a_generated_maze() {
+        let options =
+            options("--seed 7KQ2-M9XD --width 32 --height 14 --generator prim --coins 30 --npcs 0");
+        assert_eq!(options.seed, "7KQ2-M9XD".parse().unwrap());
+        assert_eq!(options.maze.width, 15);
+        assert_eq!(options.maze.height, MazeConfig::MIN_SIZE);
+        assert_eq!(MazeConfig::tiles(options.maze.width), 32);
+        assert_eq!(options.maze.algorithm, MazeAlgorithm::Prim);
+        assert!(!options.maze.static_layout);
+        assert_eq!((options.maze.coins, options.maze.npcs), (30, 0));
+
+        let options = options("--width 64 --height 64 --coins 1 --npcs 5");
+        assert_eq!(options.maze.width, MazeConfig::MAX_SIZE);
+        assert_eq!((options.maze.coins, options.maze.npcs), (1, 5));
+    }
+
+    #[test]
+    fn accepts_the_static_maze() {
+        let options = options("--static-maze --width 64 --height 64");
+        assert!(options.maze.static_layout);
+    }
+
+    #[test]
+    fn accepts_timing_window_and_recording_options() {
+        let options = options("--tick-hz 15 --continuous --fullscreen --record game.spkr");
+        assert_eq!(options.tick_hz, 15.0);
+        assert_eq!(
+            options.movement_mode,
+            MovementMode::Continuous {
+                max_speed: DEFAULT_CONTINUOUS_SPEED
+            }
+        );
+        assert!(options.fullscreen);
+        assert_eq!(options.record, Some(PathBuf::from("game.spkr")));
+
+        let options = options("--tick-hz 1 --headless --replay game.spkr");
+        assert_eq!(options.tick_hz, 1.0);
+        assert!(options.headless);
+        assert_eq!(options.replay, Some(PathBuf::from("game.spkr")));
+        assert_eq!(self::options("--tick-hz 60").tick_hz, 60.0);
+    }
+
+    #[test]
+    fn rejects_maze_sizes_outside_the_data() {
+        for args in ["--width 15", "--width 12", "--width 66", "--width x"] {
+            assert_eq!(
+                error(args),
+                "--width must be an even number from 14 to 64",
+                "{args}"
+            );
+        }
+        assert_eq!(
+            error("--height 0"),
+            "--height must be an even number from 14 to 64"
+        );
+    }
+
+    #[test]
+    fn rejects_counts_and_tick_rates_out_of_range() {
+        assert_eq!(error("--coins 0"), "--coins must be a number from 1 to 100");
+        assert_eq!(
+            error("--coins 101"),
+            "--coins must be a number from 1 to 100"
+        );
+        assert_eq!(error("--npcs 6"), "--npcs must be a number from 0 to 5");
+        assert_eq!(error("--npcs -1"), "--npcs must be a number from 0 to 5");
+        for args in ["--tick-hz 0", "--tick-hz 61", "--tick-hz fast"] {
+            assert_eq!(
+                error(args),
+                "--tick-hz must be a number from 1 to 60",
+                "{args}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_seeds_and_generators() {
+        assert_eq!(
+            error("--seed SPUKY"),
+            "invalid --seed: `U` is not a seed code digit"
+        );
+        assert_eq!(
+            error("--generator kruskal"),
+            "invalid --generator `kruskal`: unknown maze algorithm, expected `backtracking` or `prim`"
+        );
+    }
+
+    #[test]
+    fn rejects_options_that_exclude_each_other() {
+        assert_eq!(
+            error("--static-maze --width 32"),
+            "the static maze is 64x64 tiles"
+        );
+        assert_eq!(
+            error("--static-maze --height 14"),
+            "the static maze is 64x64 tiles"
+        );
+        assert_eq!(
+            error("--static-maze --generator prim"),
+            "--generator needs a generated maze, not the static one"
+        );
+        assert_eq!(
+            error("--fullscreen --headless"),
+            "--fullscreen and --headless exclude each other"
+        );
+        assert_eq!(
+            error("--record a.spkr --replay b.spkr"),
+            "--record and --replay exclude each other"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_arguments_and_missing_values() {
+        assert_eq!(error("--bogus"), "unknown argument `--bogus`");
+        assert_eq!(error("--seed"), "--seed needs a value");
+        assert_eq!(error("--width 32 --coins"), "--coins needs a value");
+    }
+
+    #[test]
+    fn help_wins_over_other_arguments() {
+        assert_eq!(parse("--help"), Ok(Command::Help));
+        assert_eq!(parse("-h"), Ok(Command::Help));
+        assert_eq!(parse("--width 32 --help"), Ok(Command::Help));
+        assert!(USAGE.contains("--replay <file>"));
+    }
+}
@@ -10,7 +10,7 @@
 // 说明：
 // 1. ESP32-S3-BOX-3 的 GT911 I2C 地址是 0x14，而非默认的 0x5d（测试发现）；参考：
 //   https://github.com/espressif/esp-bsp/blob/master/components/lcd_touch/esp_lcd_touch_gt911/include/esp_lcd_touch_gt911.h#L34C1-L40C4
-// 2. 使用较老版本的 embedded_hal 库（ 0.2.5 版本，而非新的 1.0.0 版本），从而与其他库兼容；
+// 2. 已移植到 embedded_hal 1.0.0（原版使用 0.2.5），与 esp-hal 及共享 I2C 总线兼容；
 // 3. 由于 Touch 和 LCD 共享 reset 引脚，而 LCD 初始化时已经设置了 reset，故 Touch 不需要 reset，去掉了相关逻辑，否则会导致 LCD 显示白屏；
 // 4. 自定义 Error，封装其他类型 Error，便于报错；
 // 5. 新增 IRQ 引脚和基于 irq 引脚的 data_available() 方法，后续 slint 调用该方法来判断是否有触摸事件；
@@ -23,10 +23,8 @@ use core::{array::TryFromSliceError, fmt::Debug};
 /// A minimal implementation of the GT911 to work with Lvgl since Lvgl only uses a single touch point
 /// The default orientation and size are based on the aliexpress ESP 7 inch capactive touch development
 /// board model ESP-8048S070C
-use embedded_hal::{
-    blocking::i2c::{Write, WriteRead},
-    digital::v2::InputPin,
-};
+use embedded_hal::{digital::InputPin, i2c::I2c};
+use log::debug;
 
 // 可能是两个地址：0x5d、0x14，ESP32-S3-BOX-3 开发版是 0x14，如果使用出错则会 panic。
 
@@ -139,7 +137,7 @@ pub struct GT911<I2C, IRQ> {
 
 impl<I2C, IRQ, E> GT911<I2C, IRQ>
 where
-    I2C: Write<Error = E> + WriteRead<Error = E>,
+    I2C: I2c<Error = E>,
     IRQ: InputPin,
     E: Debug,
 {
@@ -153,7 +151,7 @@ where
         }
     }
 
-    pub fn data_available(&self) -> Result<bool, Error<E>> {
+    pub fn data_available(&mut self) -> Result<bool, Error<E>> {
         self.irq_pin.is_low().map_err(|_| Error::IOError)
     }
 
@@ -185,8 +183,8 @@ where
         let lo_byte: u8 = (product_id_reg & 0xFF).try_into().unwrap();
         let tx_buf: [u8; 2] = [hi_byte, lo_byte];
 
-        self.i2c.write_read(self.address, &tx_buf, &mut rx_buf).map_err(|e| Error::BusError(e))?;
-        esp_println::println!("driver: read_product_id: {:?}", &rx_buf); // rx_buf 中的字符串应该为 "911"
+        self.i2c.write_read(self.address, &tx_buf, &mut rx_buf).map_err(Error::BusError)?;
+        debug!("driver: read_product_id: {:?}", &rx_buf); // rx_buf 中的字符串应该为 "911"
         Ok(())
     }
 
@@ -198,14 +196,14 @@ where
         let lo_byte: u8 = (point_info_reg & 0xFF).try_into().unwrap();
         let tx_buf: [u8; 2] = [hi_byte, lo_byte];
 
-        self.i2c.write_read(self.address, &tx_buf, &mut rx_buf).map_err(|e| Error::BusError(e))?;
+        self.i2c.write_read(self.address, &tx_buf, &mut rx_buf).map_err(Error::BusError)?;
 
         let point_info = rx_buf[0];
         let buffer_status = point_info & 0x80 == 0x80; // buffer status 表示触摸点数量、按键事件是否有效
         let have_key = point_info & 0x10 == 0x10; // key 数据有效
         let touches = point_info & 0xF; // 触摸点数量
 
-        esp_println::println!(
+        debug!(
             "driver: pointInfo: {:x?}, bufferStatus: {:?}, haveKey: {:?} touches: {:?}",
             point_info,
             point_info >> 7 & 1u8,
@@ -216,7 +214,7 @@ where
         if !buffer_status {
             // 没有有效的 touch key 或 touch point 数据，清理状态寄存器后返回。
             let tx_buf: [u8; 3] = [hi_byte, lo_byte, 0u8];
-            self.i2c.write(self.address, &tx_buf).map_err(|e| Error::BusError(e))?;
+            self.i2c.write(self.address, &tx_buf).map_err(Error::BusError)?;
             return Err(Error::NoDataAvailable);
         }
 
@@ -233,13 +231,13 @@ where
             // 按键或触摸释放（用 TouchEvent::None 表示释放）
             // Reset point_info register after reading it
             let tx_buf: [u8; 3] = [hi_byte, lo_byte, 0u8];
-            self.i2c.write(self.address, &tx_buf).map_err(|e| Error::BusError(e))?;
+            self.i2c.write(self.address, &tx_buf).map_err(Error::BusError)?;
             return Ok(TouchEvent::None);
         }
 
         // Reset point_info register after reading it
         let tx_buf: [u8; 3] = [hi_byte, lo_byte, 0u8];
-        self.i2c.write(self.address, &tx_buf).map_err(|e| Error::BusError(e))?;
+        self.i2c.write(self.address, &tx_buf).map_err(Error::BusError)?;
 
         Ok(te)
     }
@@ -251,13 +249,13 @@ where
 
         // GT911 有 4 个 key
         let mut rx_buf: [u8; 4] = [0; 4];
-        self.i2c.write_read(self.address, &tx_buf, &mut rx_buf).map_err(|e| Error::BusError(e))?;
+        self.i2c.write_read(self.address, &tx_buf, &mut rx_buf).map_err(Error::BusError)?;
 
-        esp_println::println!("  driver: read_touch_key: {:?}", &rx_buf);
+        debug!("  driver: read_touch_key: {:?}", &rx_buf);
 
         // ESP32-S3-Box-3 只使用了一个 touch key，故只读取 rx_buf[0] 内容
         let key0: u8 = rx_buf[0];
-        Ok(TouchKey { id: 0, pressed: if key0 == 0 { false } else { true } })
+        Ok(TouchKey { id: 0, pressed: key0 != 0 })
     }
 
     pub fn read_touch_point(&mut self, point_register: u16) -> Result<TouchPoint, Error<E>> {
@@ -266,7 +264,7 @@ where
         let tx_buf: [u8; 2] = [hi_byte, lo_byte];
 
         let mut rx_buf: [u8; 7] = [0; 7];
-        self.i2c.write_read(self.address, &tx_buf, &mut rx_buf).map_err(|e| Error::BusError(e))?;
+        self.i2c.write_read(self.address, &tx_buf, &mut rx_buf).map_err(Error::BusError)?;
 
         let id: u8 = rx_buf[0];
         let mut x: u16 = rx_buf[1] as u16 + ((rx_buf[2] as u16) << 8);
@@ -293,7 +291,7 @@ where
             }
         }
 
-        esp_println::println!("  driver: read_touch_point: x/y/id: {}/{}/{}", x, y, id);
+        debug!("  driver: read_touch_point: x/y/id: {}/{}/{}", x, y, id);
 
         Ok(TouchPoint { id, x, y, size })
     }
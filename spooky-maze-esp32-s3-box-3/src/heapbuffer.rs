@@ -1,39 +0,0 @@
-use alloc::boxed::Box;
-use embedded_graphics::prelude::*;
-use embedded_graphics_framebuf::backends::FrameBufferBackend;
-
-// ------------------------------------------------------------------------------------
-// A simple Heap‑allocated framebuffer backend for drawing to our LCD.
-pub struct HeapBuffer<C: PixelColor, const N: usize>(Box<[C; N]>);
-
-impl<C: PixelColor, const N: usize> HeapBuffer<C, N> {
-    pub fn new(data: Box<[C; N]>) -> Self {
-        Self(data)
-    }
-}
-
-impl<C: PixelColor, const N: usize> core::ops::Deref for HeapBuffer<C, N> {
-    type Target = [C; N];
-    fn deref(&self) -> &Self::Target {
-        &*self.0
-    }
-}
-
-impl<C: PixelColor, const N: usize> core::ops::DerefMut for HeapBuffer<C, N> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut *self.0
-    }
-}
-
-impl<C: PixelColor, const N: usize> FrameBufferBackend for HeapBuffer<C, N> {
-    type Color = C;
-    fn set(&mut self, index: usize, color: Self::Color) {
-        self.0[index] = color;
-    }
-    fn get(&self, index: usize) -> Self::Color {
-        self.0[index]
-    }
-    fn nr_elements(&self) -> usize {
-        N
-    }
-}
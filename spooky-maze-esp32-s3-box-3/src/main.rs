@@ -3,23 +3,18 @@
 
 extern crate alloc;
 use alloc::boxed::Box;
-use spooky_core::events::{coin::CoinCollisionMessage, player::PlayerInputMessage};
-use spooky_core::systems;
-use spooky_core::systems::hud::HudState;
-use spooky_core::systems::process_player_input::process_player_input;
-
-use bevy::DefaultPlugins;
-use bevy::app::{App, Startup};
-use bevy::prelude::Update;
-use bevy_ecs::prelude::*;
+use core::cell::RefCell;
+use core::time::Duration;
+
 use embedded_hal::delay::DelayNs;
+use embedded_hal_bus::i2c::RefCellDevice;
 use embedded_hal_bus::spi::ExclusiveDevice;
 use esp_hal::delay::Delay;
 use esp_hal::dma::{DmaRxBuf, DmaTxBuf};
 use esp_hal::dma_buffers;
 use esp_hal::{
     Blocking,
-    gpio::{DriveMode, Level, Output, OutputConfig},
+    gpio::{DriveMode, Input, InputConfig, Level, Output, OutputConfig, Pull},
     i2c::master::I2c,
     main,
     rng::Rng,
@@ -30,31 +25,29 @@ use esp_println::{logger::init_logger_from_env, println};
 use log::info;
 use mipidsi::{Builder, models::ILI9486Rgb565};
 use mipidsi::{interface::SpiInterface, options::ColorOrder};
-use spooky_core::resources::MazeSeed;
+use spooky_core::board::{Board, GameConfig, run_game};
+use spooky_core::movement::MovementMode;
+use spooky_core::render::pixel_format::Direct;
+use spooky_core::systems::tilt_input::Tilt;
 
-// Embedded Graphics imports for our framebuffer drawing.
+// Embedded Graphics imports for the display.
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
-use embedded_graphics_framebuf::FrameBuf;
+
+// Using bevy's Instant which supports set_elapsed
+use bevy_platform::time::Instant;
 
 // ESP-IDF App Descriptor required by newer espflash
 esp_bootloader_esp_idf::esp_app_desc!();
 
-mod embedded_systems {
-    pub mod player_input;
-    pub mod render;
-}
-use embedded_systems::render::render_system;
-
-mod heapbuffer;
-
-use crate::heapbuffer::HeapBuffer;
+#[allow(dead_code)]
+mod gt911;
 
 // --- NEW: Imports for the ICM-42670 accelerometer ---
 use icm42670::Icm42670;
+use icm42670::prelude::*;
 
-/// A resource wrapping the accelerometer sensor.
-/// (We make this NonSend because hardware sensor drivers typically aren’t Sync.)
+use crate::gt911::{GT911, TouchEvent};
 
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
@@ -62,33 +55,18 @@ fn panic(_info: &core::panic::PanicInfo) -> ! {
     loop {}
 }
 
-// For example, define a type alias for your concrete I2C:
-// Adjust the lifetime and driver mode if needed.
-type I2cMasterBus = esp_hal::i2c::master::I2c<'static, Blocking>;
-type I2cMasterBusError = esp_hal::i2c::master::Error;
+// The accelerometer and the GT911 touch controller share one I2C bus,
+// so each driver gets its own RefCellDevice handle to it.
+type I2cMasterBus = RefCellDevice<'static, I2c<'static, Blocking>>;
+type TouchIrq = Input<'static>;
 
 // ------------------------------------------------------------------------------------
-// LCD resolution and framebuffer definitions.
-const LCD_H_RES: usize = 320;
-const LCD_V_RES: usize = 240;
-const LCD_BUFFER_SIZE: usize = LCD_H_RES * LCD_V_RES;
+// LCD resolution.
+const LCD_H_RES: u32 = 320;
+const LCD_V_RES: u32 = 240;
 
-type FbBuffer = HeapBuffer<Rgb565, LCD_BUFFER_SIZE>;
-type MyFrameBuf = FrameBuf<Rgb565, FbBuffer>;
-
-#[derive(Resource)]
-struct FrameBufferResource {
-    frame_buf: MyFrameBuf,
-}
-
-impl FrameBufferResource {
-    fn new() -> Self {
-        let fb_data: Box<[Rgb565; LCD_BUFFER_SIZE]> = Box::new([Rgb565::BLACK; LCD_BUFFER_SIZE]);
-        let heap_buffer = HeapBuffer::new(fb_data);
-        let frame_buf = MyFrameBuf::new(heap_buffer, LCD_H_RES, LCD_V_RES);
-        Self { frame_buf }
-    }
-}
+/// Duration of one game logic step.
+const GAME_STEP_MS: u64 = 100;
 
 // Use the DMA-enabled SPI bus type.
 type MyDisplay = mipidsi::Display<
@@ -101,27 +79,82 @@ type MyDisplay = mipidsi::Display<
     Output<'static>,
 >;
 
-struct DisplayResource {
+/// Time since boot, for the board clock and Bevy's `Instant`.
+fn elapsed_time() -> Duration {
+    Duration::from_micros(
+        esp_hal::time::Instant::now()
+            .duration_since_epoch()
+            .as_micros(),
+    )
+}
+
+/// ESP32-S3-BOX-3: ILI9486 display, ICM-42670 accelerometer and GT911 touch panel.
+struct Box3 {
     display: MyDisplay,
+    sensor: Icm42670<I2cMasterBus>,
+    touch: GT911<I2cMasterBus, TouchIrq>,
+    /// The last point the GT911 reported, kept while it has no new data.
+    touch_point: Option<Point>,
+    rng: Rng,
+    delay: Delay,
 }
 
-use crate::embedded_systems::player_input;
-use crate::embedded_systems::player_input::AccelerometerResource;
-use core::sync::atomic::{AtomicU32, Ordering};
-use spooky_core::events::dynamite::DynamiteCollisionMessage;
-use spooky_core::events::npc::NpcCollisionMessage;
-use spooky_core::events::walker::WalkerCollisionMessage;
-use spooky_core::systems::collisions;
-// Using bevy's Instant which supports set_elapsed
-use bevy_platform::time::Instant;
+impl Board for Box3 {
+    type Display = MyDisplay;
+    type Palette = Direct;
+
+    fn display_size(&self) -> Size {
+        Size::new(LCD_H_RES, LCD_V_RES)
+    }
 
-static ELAPSED: AtomicU32 = AtomicU32::new(0);
-fn elapsed_time() -> core::time::Duration {
-    core::time::Duration::from_millis(ELAPSED.load(Ordering::Relaxed) as u64)
+    fn display(&mut self) -> &mut Self::Display {
+        &mut self.display
+    }
+
+    fn palette(&self) -> Direct {
+        Direct
+    }
+
+    fn seed(&mut self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        self.rng.read(&mut seed);
+        seed
+    }
+
+    fn now(&self) -> Duration {
+        elapsed_time()
+    }
+
+    fn read_tilt(&mut self) -> Option<Tilt> {
+        self.sensor.accel_norm().ok().map(|accel| Tilt {
+            x: accel.x,
+            y: accel.y,
+        })
+    }
+
+    fn has_touch(&self) -> bool {
+        true
+    }
+
+    fn read_touch(&mut self) -> Option<Point> {
+        match self.touch.read_touch() {
+            Ok(TouchEvent::Point(point)) => {
+                self.touch_point = Some(Point::new(point.x as i32, point.y as i32));
+            }
+            Ok(TouchEvent::None) => self.touch_point = None,
+            // Touch keys are not used, and "no data" simply means nothing changed.
+            Ok(TouchEvent::Key(_)) | Err(_) => {}
+        }
+        self.touch_point
+    }
+
+    fn idle(&mut self) {
+        self.delay.delay_ms(10u32);
+    }
 }
 
 // ------------------------------------------------------------------------------------
-// Our embedded main: initialize HW, set up the game world, and run the schedule.
+// Our embedded main: initialize HW and hand the board to the shared game loop.
 #[main]
 fn main() -> ! {
     // Initialize ESP‑hal peripherals.
@@ -176,54 +209,44 @@ fn main() -> ! {
     info!("Display initialized");
     unsafe { Instant::set_elapsed(elapsed_time) };
 
-    // --- Initialize the accelerometer sensor.
+    // --- Initialize the shared I2C bus, the accelerometer sensor and the touch controller.
     let i2c = I2c::new(peripherals.I2C0, esp_hal::i2c::master::Config::default())
         .unwrap()
         .with_sda(peripherals.GPIO8)
         .with_scl(peripherals.GPIO18);
-    let icm_sensor = Icm42670::new(i2c, icm42670::Address::Primary).unwrap();
-
-    let hardware_rng = Rng::new();
-    let mut seed = [0u8; 32];
-    hardware_rng.read(&mut seed);
-
-    // --- Build the Bevy app.
-    let mut app = App::new();
-    app.add_plugins((DefaultPlugins,))
-        .insert_non_send_resource(DisplayResource { display })
-        .insert_non_send_resource(AccelerometerResource { sensor: icm_sensor })
-        .insert_resource(FrameBufferResource::new())
-        .insert_resource(HudState::default())
-        .insert_resource(MazeSeed(Some(seed)))
-        .add_systems(Startup, systems::setup::setup)
-        .add_message::<PlayerInputMessage>()
-        .add_message::<CoinCollisionMessage>()
-        .add_message::<DynamiteCollisionMessage>()
-        .add_message::<WalkerCollisionMessage>()
-        .add_message::<NpcCollisionMessage>()
-        .add_systems(
-            Update,
-            (
-                player_input::dispatch_accelerometer_input::<I2cMasterBus, I2cMasterBusError>,
-                process_player_input,
-                collisions::coin::detect_coin_collision,
-                collisions::coin::remove_coin_on_collision,
-                collisions::dynamite::handle_dynamite_collision,
-                collisions::walker::detect_walker_collision,
-                collisions::walker::handle_walker_collision,
-                collisions::npc::detect_npc_collision,
-                collisions::npc::handle_npc_collision,
-                systems::dynamite_logic::handle_dynamite_collision,
-                systems::npc_logic::update_npc_movement,
-                systems::game_logic::update_game,
-                render_system,
-            ),
-        )
-        .run();
-
-    let mut loop_delay = Delay::new();
-    loop {
-        app.update();
-        loop_delay.delay_ms(50u32);
-    }
+    let i2c_bus: &'static RefCell<I2c<'static, Blocking>> = Box::leak(Box::new(RefCell::new(i2c)));
+    let icm_sensor =
+        Icm42670::new(RefCellDevice::new(i2c_bus), icm42670::Address::Primary).unwrap();
+
+    // GT911 interrupt line is on GPIO3; the reset line is shared with the LCD.
+    let touch_irq = Input::new(
+        peripherals.GPIO3,
+        InputConfig::default().with_pull(Pull::Up),
+    );
+    let touch_controller = GT911::new(RefCellDevice::new(i2c_bus), touch_irq);
+
+    // Tilt steers the ghost freely with the `continuous-movement` feature.
+    #[cfg(feature = "continuous-movement")]
+    let movement_mode = MovementMode::Continuous {
+        max_speed: spooky_core::movement::DEFAULT_CONTINUOUS_SPEED,
+    };
+    #[cfg(not(feature = "continuous-movement"))]
+    let movement_mode = MovementMode::Grid;
+
+    let board = Box3 {
+        display,
+        sensor: icm_sensor,
+        touch: touch_controller,
+        touch_point: None,
+        rng: Rng::new(),
+        delay: Delay::new(),
+    };
+    run_game(
+        board,
+        GameConfig {
+            movement_mode,
+            game_step: Duration::from_millis(GAME_STEP_MS),
+            ..GameConfig::default()
+        },
+    )
 }
@@ -1,2 +0,0 @@
-pub mod player_input;
-pub mod render;
@@ -3,15 +3,8 @@
 
 extern crate alloc;
 use alloc::boxed::Box;
-use spooky_core::events::{coin::CoinCollisionMessage, player::PlayerInputMessage};
-use spooky_core::systems;
-use spooky_core::systems::hud::HudState;
-use spooky_core::systems::process_player_input::process_player_input;
-
-use bevy::app::{App, ScheduleRunnerPlugin, Startup, TaskPoolPlugin};
-use bevy::prelude::Update;
-use bevy::time::TimePlugin;
-use bevy_ecs::prelude::*;
+use core::time::Duration;
+
 use embedded_hal::delay::DelayNs;
 use embedded_hal_bus::spi::ExclusiveDevice;
 use esp_hal::delay::Delay;
@@ -33,74 +26,34 @@ use mipidsi::{
     interface::SpiInterface,
     options::{ColorInversion, ColorOrder},
 };
-use spooky_core::resources::MazeSeed;
+use mpu6886::Mpu6886;
+use spooky_core::board::{Board, GameConfig, run_game};
+use spooky_core::render::pixel_format::Direct;
+use spooky_core::render::zoom::Zoom;
+use spooky_core::systems::tilt_input::Tilt;
 
-// Embedded Graphics imports for our framebuffer drawing.
+// Embedded Graphics imports for the display.
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
-use embedded_graphics_framebuf::FrameBuf;
+
+// Using bevy's Instant which supports set_elapsed
+use bevy_platform::time::Instant;
 
 // ESP-IDF App Descriptor required by newer espflash
 esp_bootloader_esp_idf::esp_app_desc!();
 
-// Bring in our custom render system from our embedded module.
-mod embedded_systems {
-    pub mod player_input;
-    pub mod render;
-}
-use embedded_systems::render::render_system;
-
-// Bring in our heapbuffer helper.
-mod heapbuffer;
-
-use crate::heapbuffer::HeapBuffer;
-
-// --- NEW: Imports for the ICM-42670 accelerometer ---
-// use icm42670::Icm42670;
-// use icm42670::prelude::*;
-use mpu6886::Mpu6886;
-
-/// A resource wrapping the accelerometer sensor.
-/// (We make this NonSend because hardware sensor drivers typically aren’t Sync.)
-
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
     println!("Panic: {}", _info);
     loop {}
 }
 
-// For example, define a type alias for your concrete I2C:
-// Adjust the lifetime and driver mode if needed.
 type I2cMasterBus = I2c<'static, Blocking>;
-type I2cMasterBusError = esp_hal::i2c::master::Error;
 
 // ------------------------------------------------------------------------------------
-// LCD resolution and framebuffer definitions.
-const LCD_H_RES: usize = 130;
-const LCD_V_RES: usize = 129;
-const LCD_BUFFER_SIZE: usize = LCD_H_RES * LCD_V_RES;
-
-type FbBuffer = HeapBuffer<Rgb565, LCD_BUFFER_SIZE>;
-type MyFrameBuf = FrameBuf<Rgb565, FbBuffer>;
-
-#[derive(Resource)]
-struct FrameBufferResource {
-    frame_buf: MyFrameBuf,
-}
-
-impl FrameBufferResource {
-    fn new() -> Self {
-        info!(
-            "Allocating framebuffer of size {} bytes",
-            LCD_BUFFER_SIZE * 2
-        );
-        let fb_data: Box<[Rgb565; LCD_BUFFER_SIZE]> = Box::new([Rgb565::BLACK; LCD_BUFFER_SIZE]);
-        info!("Framebuffer allocated successfully");
-        let heap_buffer = HeapBuffer::new(fb_data);
-        let frame_buf = MyFrameBuf::new(heap_buffer, LCD_H_RES, LCD_V_RES);
-        Self { frame_buf }
-    }
-}
+// LCD resolution.
+const LCD_H_RES: u32 = 130;
+const LCD_V_RES: u32 = 129;
 
 // Use the DMA-enabled SPI bus type.
 type MyDisplay = mipidsi::Display<
@@ -113,29 +66,64 @@ type MyDisplay = mipidsi::Display<
     Output<'static>,
 >;
 
-struct DisplayResource {
+/// Time since boot, for the board clock and Bevy's `Instant`.
+fn elapsed_time() -> Duration {
+    Duration::from_micros(
+        esp_hal::time::Instant::now()
+            .duration_since_epoch()
+            .as_micros(),
+    )
+}
+
+/// M5Stack Atom S3: GC9A01 display and MPU6886 accelerometer.
+struct AtomS3 {
     display: MyDisplay,
+    sensor: Mpu6886<I2cMasterBus>,
+    rng: Rng,
+    delay: Delay,
 }
 
-use crate::embedded_systems::player_input;
-use crate::embedded_systems::player_input::AccelerometerResource;
-use core::sync::atomic::{AtomicU32, Ordering};
-use spooky_core::events::dynamite::DynamiteCollisionMessage;
-use spooky_core::events::npc::NpcCollisionMessage;
-use spooky_core::events::walker::WalkerCollisionMessage;
-use spooky_core::systems::collisions;
-// Using bevy's Instant which supports set_elapsed
-use bevy_platform::time::Instant;
+impl Board for AtomS3 {
+    type Display = MyDisplay;
+    type Palette = Direct;
 
-static ELAPSED: AtomicU32 = AtomicU32::new(0);
-fn elapsed_time() -> core::time::Duration {
-    core::time::Duration::from_millis(ELAPSED.load(Ordering::Relaxed) as u64)
-}
+    fn display_size(&self) -> Size {
+        Size::new(LCD_H_RES, LCD_V_RES)
+    }
 
-// Event processing is now handled by the TimePlugin and other essential plugins
+    fn display(&mut self) -> &mut Self::Display {
+        &mut self.display
+    }
+
+    fn palette(&self) -> Direct {
+        Direct
+    }
+
+    fn seed(&mut self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        self.rng.read(&mut seed);
+        seed
+    }
+
+    fn now(&self) -> Duration {
+        elapsed_time()
+    }
+
+    fn read_tilt(&mut self) -> Option<Tilt> {
+        // The sensor's y axis points down on this board.
+        self.sensor.get_acc().ok().map(|accel| Tilt {
+            x: accel.x,
+            y: -accel.y,
+        })
+    }
+
+    fn idle(&mut self) {
+        self.delay.delay_ms(10u32);
+    }
+}
 
 // ------------------------------------------------------------------------------------
-// Our embedded main: initialize HW, set up the game world, and run the schedule.
+// Our embedded main: initialize HW and hand the board to the shared game loop.
 #[main]
 fn main() -> ! {
     // Initialize ESP‑hal peripherals.
@@ -181,7 +169,7 @@ fn main() -> ! {
 
     let mut display: MyDisplay = Builder::new(GC9A01, di)
         .reset_pin(reset)
-        .display_size(130, 129)
+        .display_size(LCD_H_RES as u16, LCD_V_RES as u16)
         // .orientation(Orientation::new().flip_horizontal())
         .color_order(ColorOrder::Bgr)
         .invert_colors(ColorInversion::Inverted)
@@ -200,10 +188,9 @@ fn main() -> ! {
         .unwrap()
         .with_sda(peripherals.GPIO38)
         .with_scl(peripherals.GPIO39);
-    // let icm_sensor = Icm42670::new(i2c, icm42670::Address::Primary).unwrap();
-    let mut icm_sensor = Mpu6886::new(i2c);
+    let mut sensor = Mpu6886::new(i2c);
     let mut delay = Delay::new();
-    match icm_sensor.init(&mut delay) {
+    match sensor.init(&mut delay) {
         Ok(_) => {
             info!("MPU6886 initialized");
         }
@@ -212,57 +199,18 @@ fn main() -> ! {
         }
     }
 
-    let hardware_rng = Rng::new();
-    let mut seed = [0u8; 32];
-    hardware_rng.read(&mut seed);
-
-    // --- Build the Bevy app with minimal essential plugins for embedded
-    let mut app = App::new();
-
-    // Add essential plugins for event processing to work
-    app.add_plugins((
-        TaskPoolPlugin::default(),       // Required for system scheduling
-        TimePlugin::default(),           // Required for frame timing and updates
-        ScheduleRunnerPlugin::default(), // Required since we don't have windowing
-    ));
-
-    // Initialize the app properly for our minimal setup
-    // app.init_resource::<Events<AppExit>>(); // Required for basic app functionality
-
-    app.insert_non_send_resource(DisplayResource { display })
-        .insert_non_send_resource(AccelerometerResource { sensor: icm_sensor })
-        .insert_resource(FrameBufferResource::new())
-        .insert_resource(HudState::default())
-        .insert_resource(MazeSeed(Some(seed)))
-        .add_systems(Startup, systems::setup::setup)
-        .add_message::<PlayerInputMessage>()
-        .add_message::<CoinCollisionMessage>()
-        .add_message::<DynamiteCollisionMessage>()
-        .add_message::<WalkerCollisionMessage>()
-        .add_message::<NpcCollisionMessage>()
-        .add_systems(
-            Update,
-            (
-                player_input::dispatch_accelerometer_input::<I2cMasterBus, I2cMasterBusError>,
-                process_player_input,
-                collisions::coin::detect_coin_collision,
-                collisions::coin::remove_coin_on_collision,
-                collisions::dynamite::handle_dynamite_collision,
-                collisions::walker::detect_walker_collision,
-                collisions::walker::handle_walker_collision,
-                collisions::npc::detect_npc_collision,
-                collisions::npc::handle_npc_collision,
-                systems::dynamite_logic::handle_dynamite_collision,
-                systems::npc_logic::update_npc_movement,
-                systems::game_logic::update_game,
-                render_system,
-            ),
-        );
-
-    let mut loop_delay = Delay::new();
-    loop {
-        app.update();
-        info!("tick");
-        loop_delay.delay_ms(300u32);
-    }
+    let board = AtomS3 {
+        display,
+        sensor,
+        rng: Rng::new(),
+        delay,
+    };
+    run_game(
+        board,
+        GameConfig {
+            // 130x129 pixels show too little of the maze at full size.
+            zoom: Zoom::Half,
+            ..GameConfig::default()
+        },
+    )
 }
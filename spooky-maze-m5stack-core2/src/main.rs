@@ -4,16 +4,10 @@
 extern crate alloc;
 use alloc::boxed::Box;
 use core::cell::RefCell;
-use spooky_core::events::{coin::CoinCollisionMessage, player::PlayerInputMessage};
-use spooky_core::systems;
-use spooky_core::systems::hud::HudState;
-use spooky_core::systems::process_player_input::process_player_input;
-
-use bevy::app::{App, ScheduleRunnerPlugin, Startup, TaskPoolPlugin};
-use bevy::prelude::Update;
-use bevy::time::TimePlugin;
-use bevy_ecs::prelude::*;
+use core::time::Duration;
+
 use embedded_hal::delay::DelayNs;
+use embedded_hal_bus::i2c::RefCellDevice;
 use embedded_hal_bus::spi::ExclusiveDevice;
 use esp_hal::delay::Delay;
 use esp_hal::dma::{DmaRxBuf, DmaTxBuf};
@@ -37,33 +31,23 @@ use mipidsi::{
     options::{ColorInversion, ColorOrder},
 };
 use mpu6886::Mpu6886;
-use spooky_core::resources::MazeSeed;
+use spooky_core::board::{Board, GameConfig, run_game};
+use spooky_core::render::pixel_format::Direct;
+use spooky_core::systems::tilt_input::Tilt;
 
-// Embedded Graphics imports for our framebuffer drawing.
+// Embedded Graphics imports for the display.
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
-use embedded_graphics_framebuf::FrameBuf;
 
 // M5Stack Core2 Power Management
 use axp192::Axp192;
 
+// Using bevy's Instant which supports set_elapsed
+use bevy_platform::time::Instant;
+
 // ESP-IDF App Descriptor required by newer espflash
 esp_bootloader_esp_idf::esp_app_desc!();
 
-// Bring in our custom render system from our embedded module.
-mod embedded_systems {
-    pub mod player_input;
-    pub mod render;
-}
-use embedded_systems::player_input;
-use embedded_systems::player_input::AccelerometerResource;
-use embedded_systems::render::render_system;
-
-// Bring in our heapbuffer helper.
-mod heapbuffer;
-
-use crate::heapbuffer::HeapBuffer;
-
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
     println!("Panic: {}", _info);
@@ -71,33 +55,10 @@ fn panic(_info: &core::panic::PanicInfo) -> ! {
 }
 
 // ------------------------------------------------------------------------------------
-// LCD resolution and framebuffer definitions.
+// LCD resolution.
 // Core2 has 320x240 display
-const LCD_H_RES: usize = 320;
-const LCD_V_RES: usize = 240;
-const LCD_BUFFER_SIZE: usize = LCD_H_RES * LCD_V_RES;
-
-type FbBuffer = HeapBuffer<Rgb565, LCD_BUFFER_SIZE>;
-type MyFrameBuf = FrameBuf<Rgb565, FbBuffer>;
-
-#[derive(Resource)]
-struct FrameBufferResource {
-    frame_buf: MyFrameBuf,
-}
-
-impl FrameBufferResource {
-    fn new() -> Self {
-        info!(
-            "Allocating framebuffer of size {} bytes",
-            LCD_BUFFER_SIZE * 2
-        );
-        let fb_data: Box<[Rgb565; LCD_BUFFER_SIZE]> = Box::new([Rgb565::BLACK; LCD_BUFFER_SIZE]);
-        info!("Framebuffer allocated successfully");
-        let heap_buffer = HeapBuffer::new(fb_data);
-        let frame_buf = MyFrameBuf::new(heap_buffer, LCD_H_RES, LCD_V_RES);
-        Self { frame_buf }
-    }
-}
+const LCD_H_RES: u32 = 320;
+const LCD_V_RES: u32 = 240;
 
 // Use the DMA-enabled SPI bus type.
 type MyDisplay = mipidsi::Display<
@@ -110,27 +71,71 @@ type MyDisplay = mipidsi::Display<
     Output<'static>,
 >;
 
-struct DisplayResource {
+type Imu = Mpu6886<RefCellDevice<'static, I2c<'static, Blocking>>>;
+
+/// Time since boot, for the board clock and Bevy's `Instant`.
+fn elapsed_time() -> Duration {
+    Duration::from_micros(
+        esp_hal::time::Instant::now()
+            .duration_since_epoch()
+            .as_micros(),
+    )
+}
+
+/// M5Stack Core2: ILI9342C display and MPU6886 accelerometer.
+struct Core2 {
     display: MyDisplay,
+    imu: Imu,
+    rng: Rng,
+    delay: Delay,
 }
 
-use core::sync::atomic::{AtomicU32, Ordering};
-use spooky_core::events::dynamite::DynamiteCollisionMessage;
-use spooky_core::events::npc::NpcCollisionMessage;
-use spooky_core::events::walker::WalkerCollisionMessage;
-use spooky_core::systems::collisions;
-// Using bevy's Instant which supports set_elapsed
-use bevy_platform::time::Instant;
+impl Board for Core2 {
+    type Display = MyDisplay;
+    type Palette = Direct;
 
-static ELAPSED: AtomicU32 = AtomicU32::new(0);
-fn elapsed_time() -> core::time::Duration {
-    core::time::Duration::from_millis(ELAPSED.load(Ordering::Relaxed) as u64)
-}
+    fn display_size(&self) -> Size {
+        Size::new(LCD_H_RES, LCD_V_RES)
+    }
+
+    fn display(&mut self) -> &mut Self::Display {
+        &mut self.display
+    }
+
+    fn palette(&self) -> Direct {
+        Direct
+    }
+
+    fn seed(&mut self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        self.rng.read(&mut seed);
+        seed
+    }
 
-// Event processing is now handled by the TimePlugin and other essential plugins
+    fn now(&self) -> Duration {
+        elapsed_time()
+    }
+
+    fn read_tilt(&mut self) -> Option<Tilt> {
+        // The sensor's x axis is inverted relative to the screen.
+        self.imu.get_acc().ok().map(|accel| Tilt {
+            x: -accel.x,
+            y: accel.y,
+        })
+    }
+
+    fn tilt_threshold(&self) -> f32 {
+        // Lower than the default for easier control.
+        0.15
+    }
+
+    fn idle(&mut self) {
+        self.delay.delay_ms(10u32);
+    }
+}
 
 // ------------------------------------------------------------------------------------
-// Our embedded main: initialize HW, set up the game world, and run the schedule.
+// Our embedded main: initialize HW and hand the board to the shared game loop.
 #[main]
 fn main() -> ! {
     // Initialize ESP‑hal peripherals.
@@ -166,7 +171,7 @@ fn main() -> ! {
 
     // --- Initialize AXP192 Power Management IC ---
     info!("Initializing AXP192 Power Management IC");
-    let axp_i2c = embedded_hal_bus::i2c::RefCellDevice::new(i2c_bus);
+    let axp_i2c = RefCellDevice::new(i2c_bus);
     let mut axp = Axp192::new(axp_i2c);
 
     // Configure AXP192 for M5Stack Core2
@@ -260,7 +265,7 @@ fn main() -> ! {
     let mut imu_delay = Delay::new();
     imu_delay.delay_ms(100u32);
 
-    let imu_i2c = embedded_hal_bus::i2c::RefCellDevice::new(i2c_bus);
+    let imu_i2c = RefCellDevice::new(i2c_bus);
     let mut imu = Mpu6886::new(imu_i2c);
     match imu.init(&mut imu_delay) {
         Ok(_) => info!("MPU6886 initialized successfully"),
@@ -272,56 +277,11 @@ fn main() -> ! {
 
     info!("MPU6886 IMU ready");
 
-    let hardware_rng = Rng::new();
-    let mut seed = [0u8; 32];
-    hardware_rng.read(&mut seed);
-
-    // --- Build the Bevy app with minimal essential plugins for embedded
-    let mut app = App::new();
-
-    // Add essential plugins for event processing to work
-    app.add_plugins((
-        TaskPoolPlugin::default(),       // Required for system scheduling
-        TimePlugin::default(),           // Required for frame timing and updates
-        ScheduleRunnerPlugin::default(), // Required since we don't have windowing
-    ));
-
-    app.insert_non_send_resource(DisplayResource { display })
-        .insert_non_send_resource(AccelerometerResource { sensor: imu })
-        .insert_resource(FrameBufferResource::new())
-        .insert_resource(HudState::default())
-        .insert_resource(MazeSeed(Some(seed)))
-        .add_systems(Startup, systems::setup::setup)
-        .add_message::<PlayerInputMessage>()
-        .add_message::<CoinCollisionMessage>()
-        .add_message::<DynamiteCollisionMessage>()
-        .add_message::<WalkerCollisionMessage>()
-        .add_message::<NpcCollisionMessage>()
-        .add_systems(
-            Update,
-            (
-                // MPU6886 IMU input
-                player_input::dispatch_accelerometer_input::<
-                    embedded_hal_bus::i2c::RefCellDevice<'static, I2c<'static, Blocking>>,
-                >,
-                process_player_input,
-                collisions::coin::detect_coin_collision,
-                collisions::coin::remove_coin_on_collision,
-                collisions::dynamite::handle_dynamite_collision,
-                collisions::walker::detect_walker_collision,
-                collisions::walker::handle_walker_collision,
-                collisions::npc::detect_npc_collision,
-                collisions::npc::handle_npc_collision,
-                systems::dynamite_logic::handle_dynamite_collision,
-                systems::npc_logic::update_npc_movement,
-                systems::game_logic::update_game,
-                render_system,
-            ),
-        );
-
-    let mut loop_delay = Delay::new();
-    loop {
-        app.update();
-        loop_delay.delay_ms(300u32);
-    }
+    let board = Core2 {
+        display,
+        imu,
+        rng: Rng::new(),
+        delay: Delay::new(),
+    };
+    run_game(board, GameConfig::default())
 }
@@ -4,16 +4,10 @@
 extern crate alloc;
 use alloc::boxed::Box;
 use core::cell::RefCell;
-use spooky_core::events::{coin::CoinCollisionMessage, player::PlayerInputMessage};
-use spooky_core::systems;
-use spooky_core::systems::hud::HudState;
-use spooky_core::systems::process_player_input::process_player_input;
-
-use bevy::app::{App, ScheduleRunnerPlugin, Startup, TaskPoolPlugin};
-use bevy::prelude::Update;
-use bevy::time::TimePlugin;
-use bevy_ecs::prelude::*;
+use core::time::Duration;
+
 use embedded_hal::delay::DelayNs;
+use embedded_hal_bus::i2c::RefCellDevice;
 use embedded_hal_bus::spi::ExclusiveDevice;
 use esp_hal::delay::Delay;
 use esp_hal::dma::{DmaRxBuf, DmaTxBuf};
@@ -32,39 +26,30 @@ use log::{error, info};
 
 // IMU - CoreS3 uses BMI270 (NOT MPU6886!)
 use bmi2::Bmi2;
+use bmi2::interface::I2cInterface;
 use mipidsi::{Builder, models::ILI9342CRgb565};
 use mipidsi::{
     interface::SpiInterface,
     options::{ColorInversion, ColorOrder},
 };
-use spooky_core::resources::MazeSeed;
+use spooky_core::board::{Board, GameConfig, run_game};
+use spooky_core::render::pixel_format::Direct;
+use spooky_core::systems::tilt_input::Tilt;
 
-// Embedded Graphics imports for our framebuffer drawing.
+// Embedded Graphics imports for the display.
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
-use embedded_graphics_framebuf::FrameBuf;
 
 // M5Stack CoreS3 Power Management and GPIO Expander
 use aw9523::{Aw9523, I2CGpioExpanderInterface};
 use axp2101_embedded::Axp2101;
 
+// Using bevy's Instant which supports set_elapsed
+use bevy_platform::time::Instant;
+
 // ESP-IDF App Descriptor required by newer espflash
 esp_bootloader_esp_idf::esp_app_desc!();
 
-// Bring in our custom render system from our embedded module.
-mod embedded_systems {
-    pub mod player_input;
-    pub mod render;
-}
-use embedded_systems::player_input;
-use embedded_systems::player_input::AccelerometerResource;
-use embedded_systems::render::render_system;
-
-// Bring in our heapbuffer helper.
-mod heapbuffer;
-
-use crate::heapbuffer::HeapBuffer;
-
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
     println!("Panic: {}", _info);
@@ -72,33 +57,13 @@ fn panic(_info: &core::panic::PanicInfo) -> ! {
 }
 
 // ------------------------------------------------------------------------------------
-// LCD resolution and framebuffer definitions.
+// LCD resolution.
 // CoreS3 has 320x240 display
-const LCD_H_RES: usize = 320;
-const LCD_V_RES: usize = 240;
-const LCD_BUFFER_SIZE: usize = LCD_H_RES * LCD_V_RES;
-
-type FbBuffer = HeapBuffer<Rgb565, LCD_BUFFER_SIZE>;
-type MyFrameBuf = FrameBuf<Rgb565, FbBuffer>;
-
-#[derive(Resource)]
-struct FrameBufferResource {
-    frame_buf: MyFrameBuf,
-}
+const LCD_H_RES: u32 = 320;
+const LCD_V_RES: u32 = 240;
 
-impl FrameBufferResource {
-    fn new() -> Self {
-        info!(
-            "Allocating framebuffer of size {} bytes",
-            LCD_BUFFER_SIZE * 2
-        );
-        let fb_data: Box<[Rgb565; LCD_BUFFER_SIZE]> = Box::new([Rgb565::BLACK; LCD_BUFFER_SIZE]);
-        info!("Framebuffer allocated successfully");
-        let heap_buffer = HeapBuffer::new(fb_data);
-        let frame_buf = MyFrameBuf::new(heap_buffer, LCD_H_RES, LCD_V_RES);
-        Self { frame_buf }
-    }
-}
+/// Raw BMI270 reading of 1 g in its default range of +-8 g.
+const ACC_LSB_PER_G: f32 = 4096.0;
 
 // Use the DMA-enabled SPI bus type.
 type MyDisplay = mipidsi::Display<
@@ -111,27 +76,71 @@ type MyDisplay = mipidsi::Display<
     Output<'static>,
 >;
 
-struct DisplayResource {
+type Imu = Bmi2<I2cInterface<RefCellDevice<'static, I2c<'static, Blocking>>>>;
+
+/// Time since boot, for the board clock and Bevy's `Instant`.
+fn elapsed_time() -> Duration {
+    Duration::from_micros(
+        esp_hal::time::Instant::now()
+            .duration_since_epoch()
+            .as_micros(),
+    )
+}
+
+/// M5Stack CoreS3: ILI9342C display and BMI270 accelerometer.
+struct CoreS3 {
     display: MyDisplay,
+    imu: Imu,
+    rng: Rng,
+    delay: Delay,
 }
 
-use core::sync::atomic::{AtomicU32, Ordering};
-use spooky_core::events::dynamite::DynamiteCollisionMessage;
-use spooky_core::events::npc::NpcCollisionMessage;
-use spooky_core::events::walker::WalkerCollisionMessage;
-use spooky_core::systems::collisions;
-// Using bevy's Instant which supports set_elapsed
-use bevy_platform::time::Instant;
+impl Board for CoreS3 {
+    type Display = MyDisplay;
+    type Palette = Direct;
 
-static ELAPSED: AtomicU32 = AtomicU32::new(0);
-fn elapsed_time() -> core::time::Duration {
-    core::time::Duration::from_millis(ELAPSED.load(Ordering::Relaxed) as u64)
-}
+    fn display_size(&self) -> Size {
+        Size::new(LCD_H_RES, LCD_V_RES)
+    }
+
+    fn display(&mut self) -> &mut Self::Display {
+        &mut self.display
+    }
+
+    fn palette(&self) -> Direct {
+        Direct
+    }
+
+    fn seed(&mut self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        self.rng.read(&mut seed);
+        seed
+    }
+
+    fn now(&self) -> Duration {
+        elapsed_time()
+    }
 
-// Event processing is now handled by the TimePlugin and other essential plugins
+    fn read_tilt(&mut self) -> Option<Tilt> {
+        // The sensor's x axis is inverted relative to the screen.
+        self.imu.get_acc_data().ok().map(|accel| Tilt {
+            x: -(accel.x as f32) / ACC_LSB_PER_G,
+            y: accel.y as f32 / ACC_LSB_PER_G,
+        })
+    }
+
+    fn tilt_threshold(&self) -> f32 {
+        // 600 raw counts: more sensitive than the default.
+        600.0 / ACC_LSB_PER_G
+    }
+
+    fn idle(&mut self) {
+        self.delay.delay_ms(10u32);
+    }
+}
 
 // ------------------------------------------------------------------------------------
-// Our embedded main: initialize HW, set up the game world, and run the schedule.
+// Our embedded main: initialize HW and hand the board to the shared game loop.
 #[main]
 fn main() -> ! {
     // Initialize ESP‑hal peripherals.
@@ -166,7 +175,7 @@ fn main() -> ! {
 
     // --- Initialize AXP2101 Power Management IC ---
     info!("Initializing AXP2101 Power Management IC");
-    let axp_i2c = embedded_hal_bus::i2c::RefCellDevice::new(i2c_bus);
+    let axp_i2c = RefCellDevice::new(i2c_bus);
     let mut axp = Axp2101::new(axp_i2c);
     match axp.init() {
         Ok(_) => info!("AXP2101 initialized successfully"),
@@ -182,7 +191,7 @@ fn main() -> ! {
 
     // --- Initialize AW9523 GPIO Expander ---
     info!("Initializing AW9523 GPIO Expander");
-    let aw_i2c = embedded_hal_bus::i2c::RefCellDevice::new(i2c_bus);
+    let aw_i2c = RefCellDevice::new(i2c_bus);
     let aw_interface = I2CGpioExpanderInterface::new(aw_i2c);
     let mut aw = Aw9523::new(aw_interface);
     match aw.init() {
@@ -254,7 +263,7 @@ fn main() -> ! {
     // Try default address first (0x68), then alternative (0x69) if that fails
     let mut imu = {
         info!("Trying BMI270 at I2C address 0x68 (default)");
-        let imu_i2c_1 = embedded_hal_bus::i2c::RefCellDevice::new(i2c_bus);
+        let imu_i2c_1 = RefCellDevice::new(i2c_bus);
         let mut imu_try = Bmi2::new_i2c(
             imu_i2c_1,
             bmi2::I2cAddr::Default,
@@ -268,7 +277,7 @@ fn main() -> ! {
             Err(e) => {
                 error!("BMI270 at 0x68 failed: {:?}", e);
                 info!("Trying alternative I2C address 0x69");
-                let imu_i2c_2 = embedded_hal_bus::i2c::RefCellDevice::new(i2c_bus);
+                let imu_i2c_2 = RefCellDevice::new(i2c_bus);
                 let mut imu_alt = Bmi2::new_i2c(
                     imu_i2c_2,
                     bmi2::I2cAddr::Alternative,
@@ -298,57 +307,11 @@ fn main() -> ! {
 
     info!("BMI270 IMU ready");
 
-    let hardware_rng = Rng::new();
-    let mut seed = [0u8; 32];
-    hardware_rng.read(&mut seed);
-
-    // --- Build the Bevy app with minimal essential plugins for embedded
-    let mut app = App::new();
-
-    // Add essential plugins for event processing to work
-    app.add_plugins((
-        TaskPoolPlugin::default(),       // Required for system scheduling
-        TimePlugin::default(),           // Required for frame timing and updates
-        ScheduleRunnerPlugin::default(), // Required since we don't have windowing
-    ));
-
-    app.insert_non_send_resource(DisplayResource { display })
-        .insert_non_send_resource(AccelerometerResource { sensor: imu })
-        .insert_resource(FrameBufferResource::new())
-        .insert_resource(HudState::default())
-        .insert_resource(MazeSeed(Some(seed)))
-        .add_systems(Startup, systems::setup::setup)
-        .add_message::<PlayerInputMessage>()
-        .add_message::<CoinCollisionMessage>()
-        .add_message::<DynamiteCollisionMessage>()
-        .add_message::<WalkerCollisionMessage>()
-        .add_message::<NpcCollisionMessage>()
-        .add_systems(
-            Update,
-            (
-                // MPU6886 IMU input using mpu6050-dmp driver
-                // Note: Using a simple type since we're not sharing I2C with RefCell here
-                player_input::dispatch_accelerometer_input::<
-                    embedded_hal_bus::i2c::RefCellDevice<'static, I2c<'static, Blocking>>,
-                >,
-                process_player_input,
-                collisions::coin::detect_coin_collision,
-                collisions::coin::remove_coin_on_collision,
-                collisions::dynamite::handle_dynamite_collision,
-                collisions::walker::detect_walker_collision,
-                collisions::walker::handle_walker_collision,
-                collisions::npc::detect_npc_collision,
-                collisions::npc::handle_npc_collision,
-                systems::dynamite_logic::handle_dynamite_collision,
-                systems::npc_logic::update_npc_movement,
-                systems::game_logic::update_game,
-                render_system,
-            ),
-        );
-
-    let mut loop_delay = Delay::new();
-    loop {
-        app.update();
-        loop_delay.delay_ms(300u32);
-    }
+    let board = CoreS3 {
+        display,
+        imu,
+        rng: Rng::new(),
+        delay: Delay::new(),
+    };
+    run_game(board, GameConfig::default())
 }
@@ -0,0 +1,283 @@
+//! Runs the embedded build of the game on a laptop.
+//!
+//! spooky-core is built without `std`, so the game uses `NoStdSprite`/`NoStdTransform`,
+//! the BMP assets and the framebuffer renderer of the boards; the app is the one
+//! `board::build_app` builds for every board, running on a `VirtualBoard`. Frames go
+//! to a window or, with `--headless`, to PNG files. Tilt comes from a script
+//! (`--tilt`) or, in the window, from the arrow keys.
+
+mod sim_systems;
+
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use bevy::app::App;
+use embedded_graphics::prelude::Size;
+use spooky_core::board::{self, GameConfig};
+use spooky_core::movement::{DEFAULT_CONTINUOUS_SPEED, MovementMode};
+use spooky_core::render::pixel_format::{Monochrome, Tone};
+use spooky_core::render::zoom::Zoom;
+use spooky_core::seed_code::SeedCode;
+use spooky_core::systems::tilt_input::Tilt;
+use spooky_host::VirtualBoard;
+
+use crate::sim_systems::tilt::TiltScript;
+
+/// Duration of one game logic step, as on the boards.
+const GAME_STEP_MS: u64 = 300;
+/// Simulated time per frame. The clock is virtual, so headless runs are reproducible.
+const FRAME_MS: u64 = 33;
+
+/// Display of a board the simulator can pretend to be.
+struct BoardProfile {
+    name: &'static str,
+    size: Size,
+    zoom: Zoom,
+    /// Tone of a 1-bit panel, shown in black and white; `None` for colour panels.
+    monochrome: Option<Tone>,
+}
+
+const BOARDS: &[BoardProfile] = &[
+    BoardProfile {
+        name: "esp32-s3-box-3",
+        size: Size::new(320, 240),
+        zoom: Zoom::Normal,
+        monochrome: None,
+    },
+    BoardProfile {
+        name: "m5stack-core2",
+        size: Size::new(320, 240),
+        zoom: Zoom::Normal,
+        monochrome: None,
+    },
+    BoardProfile {
+        name: "m5stack-cores3",
+        size: Size::new(320, 240),
+        zoom: Zoom::Normal,
+        monochrome: None,
+    },
+    BoardProfile {
+        name: "m5stack-atom-s3",
+        size: Size::new(130, 129),
+        zoom: Zoom::Half,
+        monochrome: None,
+    },
+    BoardProfile {
+        name: "128x128",
+        size: Size::new(128, 128),
+        zoom: Zoom::Half,
+        monochrome: None,
+    },
+    BoardProfile {
+        name: "ssd1306",
+        size: Size::new(128, 64),
+        zoom: Zoom::Half,
+        monochrome: Some(Tone::Dither),
+    },
+];
+
+const USAGE: &str = "\
+Usage: spooky-maze-sim [options]
+
+  --board <name>     esp32-s3-box-3 (default), m5stack-core2, m5stack-cores3,
+                     m5stack-atom-s3, 128x128 or ssd1306 (128x64, 1-bit)
+  --seed <code>      maze seed code (default 0000-0000)
+  --tilt <script>    tilt samples as `x,y:frames` entries, e.g. \"0.5,0:30;0,-0.5:15\"
+  --continuous       free movement instead of tile steps
+  --headless         write frames as PNG instead of opening a window
+  --frames <n>       frames to run headless (default: length of the tilt script or 300)
+  --every <n>        write every n-th frame (default 1)
+  --out <dir>        directory for the PNG files (default sim-frames)";
+
+struct Options {
+    board: &'static BoardProfile,
+    seed: SeedCode,
+    tilt: TiltScript,
+    continuous: bool,
+    headless: bool,
+    frames: Option<u32>,
+    every: u32,
+    out: PathBuf,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        board: &BOARDS[0],
+        seed: SeedCode(0),
+        tilt: TiltScript::default(),
+        continuous: false,
+        headless: false,
+        frames: None,
+        every: 1,
+        out: PathBuf::from("sim-frames"),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--board" => {
+                let name = value()?;
+                options.board = BOARDS
+                    .iter()
+                    .find(|board| board.name == name)
+                    .ok_or_else(|| format!("unknown board `{name}`"))?;
+            }
+            "--seed" => {
+                options.seed = value()?
+                    .parse()
+                    .map_err(|error| format!("invalid --seed: {error}"))?
+            }
+            "--tilt" => options.tilt = TiltScript::parse(&value()?)?,
+            "--continuous" => options.continuous = true,
+            "--headless" => options.headless = true,
+            "--frames" => {
+                let frames = value()?.parse().map_err(|_| "invalid --frames")?;
+                options.frames = Some(frames);
+            }
+            "--every" => options.every = value()?.parse().map_err(|_| "invalid --every")?,
+            "--out" => options.out = PathBuf::from(value()?),
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => return Err(format!("unknown argument `{arg}`")),
+        }
+    }
+    options.every = options.every.max(1);
+    Ok(options)
+}
+
+/// The game on a `VirtualBoard` with the display of the chosen board.
+fn build_app(options: &Options) -> App {
+    let movement_mode = if options.continuous {
+        MovementMode::Continuous {
+            max_speed: DEFAULT_CONTINUOUS_SPEED,
+        }
+    } else {
+        MovementMode::Grid
+    };
+    let mut board = VirtualBoard::new(options.board.size, options.seed.to_seed());
+    board.frame_time = Duration::from_millis(FRAME_MS);
+    board.monochrome = options.board.monochrome.map(Monochrome);
+    board::build_app(
+        board,
+        GameConfig {
+            zoom: options.board.zoom,
+            movement_mode,
+            game_step: Duration::from_millis(GAME_STEP_MS),
+            ..GameConfig::default()
+        },
+    )
+}
+
+/// Feed one tilt sample to the board and advance the game by one frame.
+fn step(app: &mut App, tilt: Tilt) {
+    VirtualBoard::of_mut(app).tilt = Some(tilt);
+    board::update::<VirtualBoard>(app);
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|error| {
+        eprintln!("{error}\n\n{USAGE}");
+        process::exit(2);
+    });
+    let mut app = build_app(&options);
+
+    if options.headless {
+        run_headless(&mut app, &options);
+    } else {
+        run_window(&mut app, &options);
+    }
+}
+
+/// Run a fixed number of frames and write them as PNG files.
+fn run_headless(app: &mut App, options: &Options) {
+    let frames = options.frames.unwrap_or(if options.tilt.steps.is_empty() {
+        300
+    } else {
+        options.tilt.frames()
+    });
+    if let Err(error) = std::fs::create_dir_all(&options.out) {
+        eprintln!("cannot create {}: {error}", options.out.display());
+        process::exit(1);
+    }
+    for frame in 0..frames {
+        step(app, options.tilt.sample(frame));
+        if frame % options.every == 0 {
+            let path = options.out.join(format!("frame_{frame:05}.png"));
+            if let Err(error) = VirtualBoard::of(app).display.save_png(&path) {
+                eprintln!("cannot write {}: {error}", path.display());
+                process::exit(1);
+            }
+        }
+    }
+    println!(
+        "{} frames of {} written to {}",
+        frames.div_ceil(options.every),
+        options.board.name,
+        options.out.display()
+    );
+}
+
+#[cfg(feature = "window")]
+fn run_window(app: &mut App, options: &Options) {
+    use minifb::{Key, Scale, Window, WindowOptions};
+
+    let size = options.board.size;
+    let mut window = Window::new(
+        &format!("Spooky Maze - {}", options.board.name),
+        size.width as usize,
+        size.height as usize,
+        WindowOptions {
+            scale: if size.width < 240 {
+                Scale::X4
+            } else {
+                Scale::X2
+            },
+            ..WindowOptions::default()
+        },
+    )
+    .unwrap_or_else(|error| {
+        eprintln!("cannot open a window: {error}");
+        process::exit(1);
+    });
+    window.set_target_fps((1000 / FRAME_MS) as usize);
+
+    let mut buffer = vec![0u32; (size.width * size.height) as usize];
+    let mut frame = 0;
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        // The arrow keys tilt the board by half a g and override the script.
+        let axis = |negative: Key, positive: Key| {
+            (window.is_key_down(positive) as i32 - window.is_key_down(negative) as i32) as f32 * 0.5
+        };
+        let keys = Tilt {
+            x: axis(Key::Left, Key::Right),
+            y: axis(Key::Down, Key::Up),
+        };
+        let tilt = if keys == Tilt::default() {
+            options.tilt.sample(frame)
+        } else {
+            keys
+        };
+        step(app, tilt);
+        frame += 1;
+
+        let display = &VirtualBoard::of(app).display;
+        for (target, rgb) in buffer.iter_mut().zip(display.to_rgb888().chunks_exact(3)) {
+            *target = u32::from_be_bytes([0, rgb[0], rgb[1], rgb[2]]);
+        }
+        if let Err(error) =
+            window.update_with_buffer(&buffer, size.width as usize, size.height as usize)
+        {
+            eprintln!("cannot update the window: {error}");
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(feature = "window"))]
+fn run_window(_app: &mut App, _options: &Options) {
+    eprintln!("built without the `window` feature; use --headless");
+    process::exit(2);
+}
@@ -0,0 +1,48 @@
+use spooky_core::systems::tilt_input::Tilt;
+
+/// A sequence of synthetic tilt samples (in g), each held for a number of frames.
+/// After the last entry the board lies flat.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TiltScript {
+    pub steps: Vec<(Tilt, u32)>,
+}
+
+impl TiltScript {
+    /// Parse `x,y:frames` entries separated by `;` or whitespace, e.g.
+    /// `0.5,0:30; 0,-0.5:15`.
+    pub fn parse(script: &str) -> Result<Self, String> {
+        let steps = script
+            .split(|c: char| c == ';' || c.is_whitespace())
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let invalid = || format!("invalid tilt entry `{entry}`, expected x,y:frames");
+                let (sample, frames) = entry.split_once(':').ok_or_else(invalid)?;
+                let (x, y) = sample.split_once(',').ok_or_else(invalid)?;
+                Ok((
+                    Tilt {
+                        x: x.trim().parse().map_err(|_| invalid())?,
+                        y: y.trim().parse().map_err(|_| invalid())?,
+                    },
+                    frames.trim().parse().map_err(|_| invalid())?,
+                ))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { steps })
+    }
+
+    /// Number of frames the script covers.
+    pub fn frames(&self) -> u32 {
+        self.steps.iter().map(|(_, frames)| frames).sum()
+    }
+
+    pub fn sample(&self, frame: u32) -> Tilt {
+        let mut start = 0;
+        for (sample, frames) in &self.steps {
+            if frame < start + frames {
+                return *sample;
+            }
+            start += frames;
+        }
+        Tilt::default()
+    }
+}
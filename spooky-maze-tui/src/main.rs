@@ -0,0 +1,156 @@
+//! Plays the game in a terminal.
+//!
+//! spooky-core is built without `std`, as for the boards, and only its game systems
+//! run: `board::add_game_systems` on a plain Bevy app without rendering or windowing.
+//! Each frame the maze around the player is drawn with Unicode blocks and ANSI colours
+//! below a text HUD, which is enough to play and debug over SSH.
+
+mod tui_systems;
+
+use std::io;
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bevy::app::TaskPoolPlugin;
+use bevy::prelude::*;
+use bevy::time::TimePlugin;
+use crossterm::cursor::{Hide, Show};
+use crossterm::event::{self, Event};
+use crossterm::execute;
+use crossterm::terminal::{
+    self, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use spooky_core::board::add_game_systems;
+use spooky_core::movement::MovementMode;
+use spooky_core::resources::MazeSeed;
+use spooky_core::seed_code::SeedCode;
+use spooky_core::systems::TickSet;
+use spooky_core::systems::hud::HudState;
+use spooky_core::systems::queued_input::{QueuedInput, dispatch_queued_input};
+
+use crate::tui_systems::input::{KeyCommand, map_key};
+use crate::tui_systems::screen::Screen;
+
+/// Game ticks per second, as on the desktop.
+const TICK_HZ: f64 = 10.0;
+/// Time between two redraws.
+const FRAME_MS: u64 = 33;
+
+const USAGE: &str = "\
+Usage: spooky-maze-tui [options]
+
+  --seed <code>      maze seed code (default: random)
+  --ascii            draw with ASCII characters only
+
+Keys: arrows or WASD move, Space teleports, Enter or E places dynamite, Q or Esc quits.";
+
+struct Options {
+    seed: SeedCode,
+    ascii: bool,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        seed: SeedCode::random(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_nanos() as u64)
+                .unwrap_or_default(),
+        ),
+        ascii: false,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a value")?;
+                options.seed = value
+                    .parse()
+                    .map_err(|error| format!("invalid --seed: {error}"))?;
+            }
+            "--ascii" => options.ascii = true,
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => return Err(format!("unknown argument `{arg}`")),
+        }
+    }
+    Ok(options)
+}
+
+fn build_app(options: &Options) -> App {
+    let mut app = App::new();
+    app.add_plugins((TaskPoolPlugin::default(), TimePlugin::default()));
+    add_game_systems(&mut app);
+    app.insert_resource(MazeSeed(Some(options.seed.to_seed())))
+        .insert_resource(HudState::default())
+        .insert_resource(MovementMode::Grid)
+        .insert_resource(Time::<Fixed>::from_hz(TICK_HZ))
+        .init_resource::<QueuedInput>()
+        .add_systems(FixedUpdate, dispatch_queued_input.in_set(TickSet::Input));
+    app
+}
+
+/// Raw mode on the alternate screen while it lives; restores the terminal when
+/// dropped, also when the game panics.
+struct Terminal;
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|error| {
+        eprintln!("{error}\n\n{USAGE}");
+        process::exit(2);
+    });
+    let mut app = build_app(&options);
+
+    let result = Terminal::enter().and_then(|terminal| {
+        let result = run(&mut app, &mut Screen::new(options.ascii));
+        drop(terminal);
+        result
+    });
+    if let Err(error) = result {
+        eprintln!("terminal error: {error}");
+        process::exit(1);
+    }
+    println!("Maze seed: {}", options.seed);
+}
+
+/// Read keys, advance the game and redraw until the player quits.
+fn run(app: &mut App, screen: &mut Screen) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    loop {
+        let deadline = Instant::now() + Duration::from_millis(FRAME_MS);
+        while event::poll(deadline.saturating_duration_since(Instant::now()))? {
+            match event::read()? {
+                Event::Key(key) => match map_key(&key) {
+                    Some(KeyCommand::Quit) => return Ok(()),
+                    Some(command) => {
+                        command.queue(&mut app.world_mut().resource_mut::<QueuedInput>())
+                    }
+                    None => {}
+                },
+                Event::Resize(..) => screen.invalidate(),
+                _ => {}
+            }
+        }
+
+        app.update();
+        let (columns, rows) = terminal::size()?;
+        screen.draw(&mut out, app.world(), columns, rows)?;
+    }
+}
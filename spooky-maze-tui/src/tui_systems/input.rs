@@ -0,0 +1,48 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use spooky_core::events::action::PlayerAction;
+use spooky_core::systems::queued_input::QueuedInput;
+
+/// What a key press asks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyCommand {
+    /// One tile in screen directions: positive dx is right, positive dy is down. The
+    /// screen shows the maze like the embedded renderer, so these are also the world
+    /// directions.
+    Move(i32, i32),
+    Action(PlayerAction),
+    Quit,
+}
+
+/// Arrows and WASD move, Space teleports, Enter or E places dynamite, Q, Escape or
+/// Ctrl-C quit. Terminals report no key releases, so every press and every
+/// auto-repeat of a held key counts as a tap.
+pub fn map_key(key: &KeyEvent) -> Option<KeyCommand> {
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+    let command = match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => KeyCommand::Quit,
+        KeyCode::Up | KeyCode::Char('w') | KeyCode::Char('W') => KeyCommand::Move(0, -1),
+        KeyCode::Down | KeyCode::Char('s') | KeyCode::Char('S') => KeyCommand::Move(0, 1),
+        KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('A') => KeyCommand::Move(-1, 0),
+        KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('D') => KeyCommand::Move(1, 0),
+        KeyCode::Char(' ') => KeyCommand::Action(PlayerAction::Teleport),
+        KeyCode::Enter | KeyCode::Char('e') | KeyCode::Char('E') => {
+            KeyCommand::Action(PlayerAction::PlaceDynamite)
+        }
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => KeyCommand::Quit,
+        _ => return None,
+    };
+    Some(command)
+}
+
+impl KeyCommand {
+    /// Queue the command for the next game tick; `Quit` is handled by the caller.
+    pub fn queue(self, input: &mut QueuedInput) {
+        match self {
+            KeyCommand::Move(dx, dy) => input.press(dx, dy),
+            KeyCommand::Action(action) => input.action(action),
+            KeyCommand::Quit => {}
+        }
+    }
+}
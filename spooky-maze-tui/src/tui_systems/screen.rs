@@ -0,0 +1,203 @@
+//! Draws the maze window and the HUD with characters.
+//!
+//! Every tile is two columns wide, which makes it roughly square in most terminal
+//! fonts. Row 0 of the maze is at the top, as on the boards. Lines are only rewritten
+//! when they change, which keeps the output small enough for slow SSH connections.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use bevy::prelude::*;
+use crossterm::cursor::MoveTo;
+use crossterm::queue;
+use crossterm::style::{Print, StyledContent, Stylize};
+use crossterm::terminal::{Clear, ClearType};
+use spooky_core::hud_layout::HudLayout;
+use spooky_core::maze::Maze;
+use spooky_core::resources::{MazeResource, PlayerPosition};
+use spooky_core::systems::hud::HudState;
+
+/// Lines above the maze: the HUD and the key help.
+const HUD_LINES: u16 = 2;
+/// Width of the HUD gauges in characters.
+const GAUGE_WIDTH: u32 = 10;
+const HELP: &str = "arrows/WASD move  space teleport  enter dynamite  q quit";
+
+/// What one tile of the maze window shows, from back to front.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cell {
+    Outside,
+    Tile(u8),
+    Coin,
+    Walker,
+    Dynamite,
+    Npc,
+    Ghost,
+}
+
+pub struct Screen {
+    /// Plain ASCII instead of Unicode blocks, for terminals without them.
+    ascii: bool,
+    lines: Vec<String>,
+}
+
+impl Screen {
+    pub fn new(ascii: bool) -> Self {
+        Self {
+            ascii,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Forget what is on the screen, so that the next `draw` repaints every line.
+    pub fn invalidate(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Draw the game in a terminal of `columns` x `rows` characters.
+    pub fn draw(
+        &mut self,
+        out: &mut impl Write,
+        world: &World,
+        columns: u16,
+        rows: u16,
+    ) -> io::Result<()> {
+        let maze = &world.resource::<MazeResource>().maze;
+        let player = world.resource::<PlayerPosition>();
+        let hud_state = world.resource::<HudState>();
+
+        // Lines must not wrap, or everything below them would shift.
+        let hud: String = self
+            .hud_line(hud_state)
+            .chars()
+            .take(columns as usize)
+            .collect();
+        let help = &HELP[..HELP.len().min(columns as usize)];
+        let mut lines = vec![hud, help.dark_grey().to_string()];
+        let width = columns as i32 / 2;
+        let height = rows.saturating_sub(HUD_LINES) as i32;
+        let cells = cells(maze, player, width, height);
+        for row in cells.chunks(width.max(1) as usize) {
+            let mut line = String::new();
+            for cell in row {
+                let _ = write!(line, "{}", self.glyph(*cell));
+            }
+            lines.push(line);
+        }
+        lines.truncate(rows as usize);
+
+        if self.lines.len() != lines.len() {
+            self.lines.clear();
+            queue!(out, Clear(ClearType::All))?;
+        }
+        for (index, line) in lines.iter().enumerate() {
+            if self.lines.get(index) == Some(line) {
+                continue;
+            }
+            queue!(
+                out,
+                MoveTo(0, index as u16),
+                Print(line),
+                Clear(ClearType::UntilNewLine)
+            )?;
+        }
+        self.lines = lines;
+        out.flush()
+    }
+
+    /// Every widget of the desktop layout as text, gauges as bars.
+    fn hud_line(&self, hud_state: &HudState) -> String {
+        let mut line = String::new();
+        for layout in HudLayout::DESKTOP.widgets {
+            let widget = layout.widget;
+            let value = widget.value(hud_state);
+            if !line.is_empty() {
+                line.push_str("  ");
+            }
+            match widget.maximum() {
+                Some(maximum) => {
+                    let filled = (value.min(maximum) * GAUGE_WIDTH / maximum.max(1)) as usize;
+                    let (full, empty) = if self.ascii {
+                        ('#', '-')
+                    } else {
+                        ('█', '░')
+                    };
+                    let _ = write!(line, "{} [", widget.short_label());
+                    line.extend(std::iter::repeat_n(full, filled));
+                    line.extend(std::iter::repeat_n(empty, GAUGE_WIDTH as usize - filled));
+                    line.push(']');
+                }
+                None => {
+                    let _ = write!(line, "{}: ", widget.label());
+                    let _ = widget.write_value(&mut line, hud_state);
+                }
+            }
+        }
+        line
+    }
+
+    fn glyph(&self, cell: Cell) -> StyledContent<&'static str> {
+        match cell {
+            Cell::Outside => "  ".stylize(),
+            Cell::Tile(1) => (if self.ascii { "##" } else { "██" }).dark_blue(),
+            Cell::Tile(2) => (if self.ascii { "::" } else { "░░" }).dark_yellow(),
+            Cell::Tile(_) => "  ".on_black(),
+            Cell::Coin => "()".yellow().on_black(),
+            Cell::Walker => "ww".green().on_black(),
+            Cell::Dynamite => "!!".red().on_black(),
+            Cell::Npc => "><".magenta().on_black(),
+            Cell::Ghost => "@@".white().bold().on_black(),
+        }
+    }
+}
+
+/// The `width` x `height` tiles around the player, row by row.
+fn cells(maze: &Maze, player: &PlayerPosition, width: i32, height: i32) -> Vec<Cell> {
+    let (left, bottom, _right, _top) = maze.playable_bounds();
+    let tile_of = |x: i32, y: i32| {
+        (
+            (x - left).div_euclid(maze.tile_width as i32),
+            (y - bottom).div_euclid(maze.tile_height as i32),
+        )
+    };
+    let (player_x, player_y) = tile_of(player.x as i32, player.y as i32);
+    let first_x = player_x - width / 2;
+    let first_y = player_y - height / 2;
+
+    let mut cells = Vec::with_capacity((width.max(0) * height.max(0)) as usize);
+    for y in first_y..first_y + height {
+        for x in first_x..first_x + width {
+            let inside =
+                (0..maze.width as i32).contains(&x) && (0..maze.height as i32).contains(&y);
+            cells.push(if inside {
+                Cell::Tile(maze.data[(y * maze.width as i32 + x) as usize])
+            } else {
+                Cell::Outside
+            });
+        }
+    }
+
+    let mut place = |x: i32, y: i32, cell: Cell| {
+        let (x, y) = tile_of(x, y);
+        let (column, row) = (x - first_x, y - first_y);
+        if (0..width).contains(&column) && (0..height).contains(&row) {
+            cells[(row * width + column) as usize] = cell;
+        }
+    };
+    let layers = [
+        (&maze.coins[..], Cell::Coin),
+        (&maze.walkers[..], Cell::Walker),
+        (&maze.dynamites[..], Cell::Dynamite),
+    ];
+    for (items, cell) in layers {
+        for item in items.iter().filter(|item| item.x != -1 && item.y != -1) {
+            place(item.x, item.y, cell);
+        }
+    }
+    // Unused NPC slots stay at (-1, -1).
+    for npc in &maze.npcs[..maze.npc_count] {
+        place(npc.x, npc.y, Cell::Npc);
+    }
+    place(player.x as i32, player.y as i32, Cell::Ghost);
+    cells
+}
@@ -0,0 +1,19 @@
+#!/bin/bash
+
+echo "Building Spooky Maze WASM (lite)..."
+
+# Check if wasm-pack is installed
+if ! command -v wasm-pack &> /dev/null; then
+    echo "wasm-pack not found. Installing..."
+    curl https://rustwasm.github.io/wasm-pack/installer/init.sh -sSf | sh
+fi
+
+# Release build: the size of the module is the point of this front-end.
+wasm-pack build --target web --out-dir pkg --release
+
+echo "Build complete! pkg/spooky_maze_wasm_lite_bg.wasm:"
+ls -lh pkg/spooky_maze_wasm_lite_bg.wasm
+echo ""
+echo "Serve the files with a local server, e.g.:"
+echo "  python3 -m http.server 8000"
+echo "Then open http://localhost:8000 in your browser."
@@ -0,0 +1,322 @@
+//! Lightweight browser build.
+//!
+//! Instead of full Bevy with WebGL this runs the board code path of spooky-core:
+//! `board::build_app` on a `CanvasBoard`, whose display is an RGBA buffer that is put
+//! on a `<canvas>` through `ImageData` after every frame. The page gets exactly the
+//! pixels of the embedded boards, HUD included, from a much smaller module.
+//!
+//! `SpookyMazeWasm` is the JavaScript API: input, the game state for a HUD of the
+//! page, callbacks for game events, and pause, resume and restart for menus.
+//!
+//! The page URL picks the maze: `?seed=` takes a seed code, `?size=` and `?algo=`
+//! shape the generated maze, and `share_link` gives the URL of the current game.
+
+mod web_systems;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use spooky_core::board::{self, BoardResource, GameConfig};
+use spooky_core::events::action::PlayerAction;
+use spooky_core::resources::{MazeConfig, MazeResource, MazeSeed, PlayerPosition};
+use spooky_core::seed_code::SeedCode;
+use spooky_core::systems::TickSet;
+use spooky_core::systems::hud::HudState;
+use spooky_core::systems::queued_input::{QueuedInput, dispatch_queued_input};
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use web_sys::{HtmlCanvasElement, KeyboardEvent, console};
+
+use crate::web_systems::canvas::CanvasBoard;
+use crate::web_systems::game_events::{GameEvents, collect_game_events};
+use crate::web_systems::keyboard::queue_key;
+use crate::web_systems::url_params::UrlParams;
+
+/// Duration of one game logic step, as in the Bevy build.
+const GAME_STEP_MS: u64 = 100;
+
+#[wasm_bindgen]
+pub struct SpookyMazeWasm {
+    app: Rc<RefCell<App>>,
+    listeners: Rc<RefCell<Vec<js_sys::Function>>>,
+    maze_config: MazeConfig,
+    running: Cell<bool>,
+}
+
+#[wasm_bindgen]
+impl SpookyMazeWasm {
+    /// Create the game on the canvas with the id `canvas_id`; the canvas size is the
+    /// display size, e.g. 320x240 like the ESP32-S3-BOX-3. The maze is the one of
+    /// the URL parameters.
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_id: &str) -> Result<SpookyMazeWasm, JsValue> {
+        console_error_panic_hook::set_once();
+
+        let canvas = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id(canvas_id))
+            .ok_or_else(|| JsValue::from_str(&format!("No element `{}`", canvas_id)))?
+            .dyn_into::<HtmlCanvasElement>()?;
+        let params = web_sys::window()
+            .map(|window| UrlParams::from_location(&window))
+            .unwrap_or_default();
+        let mut board = CanvasBoard::new(&canvas)?;
+        board.seed = Some(params.seed.unwrap_or_else(random_seed_code).to_seed());
+        let app = build_app(board, params.maze);
+
+        console::log_1(&"Spooky Maze initialized".into());
+        Ok(Self {
+            app: Rc::new(RefCell::new(app)),
+            listeners: Rc::new(RefCell::new(Vec::new())),
+            maze_config: params.maze,
+            running: Cell::new(false),
+        })
+    }
+
+    /// Start the frame loop on `requestAnimationFrame` and listen for keys.
+    pub fn run(&self) -> Result<(), JsValue> {
+        if self.running.replace(true) {
+            return Ok(());
+        }
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+
+        let app = self.app.clone();
+        let on_key = Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+            let mut app = app.borrow_mut();
+            if is_paused(&app) {
+                return;
+            }
+            if queue_key(
+                &event.code(),
+                &mut app.world_mut().resource_mut::<QueuedInput>(),
+            ) {
+                event.prevent_default();
+            }
+        });
+        window.add_event_listener_with_callback("keydown", on_key.as_ref().unchecked_ref())?;
+        // The listener stays for the lifetime of the page.
+        on_key.forget();
+
+        let frame: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
+        let next_frame = frame.clone();
+        let app = self.app.clone();
+        let listeners = self.listeners.clone();
+        *frame.borrow_mut() = Some(Closure::new(move || {
+            let events = {
+                let mut app = app.borrow_mut();
+                board::update::<CanvasBoard>(&mut app);
+                std::mem::take(&mut app.world_mut().resource_mut::<GameEvents>().0)
+            };
+            // The callbacks may call back into the game, so the app is not borrowed
+            // here, and listeners added by a callback only see later events.
+            let listeners = listeners.borrow().clone();
+            for event in events {
+                let value = event.value().map_or(JsValue::UNDEFINED, JsValue::from);
+                for listener in &listeners {
+                    if let Err(error) = listener.call2(&JsValue::NULL, &event.name().into(), &value)
+                    {
+                        console::error_2(&"Game event callback failed:".into(), &error);
+                    }
+                }
+            }
+            if let Some(callback) = next_frame.borrow().as_ref() {
+                request_animation_frame(callback);
+            }
+        }));
+        if let Some(callback) = frame.borrow().as_ref() {
+            request_animation_frame(callback);
+        }
+        Ok(())
+    }
+
+    /// Call `callback(name, value)` for every game event: `coin_collected` with the
+    /// coins left, `caught_by_npc`, and `level_complete` with the level.
+    pub fn on_game_event(&self, callback: js_sys::Function) {
+        self.listeners.borrow_mut().push(callback);
+    }
+
+    /// Stop the game clock; the last frame stays on the canvas and input is ignored.
+    pub fn pause(&self) {
+        let mut app = self.app.borrow_mut();
+        app.world_mut().resource_mut::<Time<Virtual>>().pause();
+        app.world_mut().resource_mut::<QueuedInput>().clear();
+    }
+
+    pub fn resume(&self) {
+        let mut app = self.app.borrow_mut();
+        app.world_mut().resource_mut::<Time<Virtual>>().unpause();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        is_paused(&self.app.borrow())
+    }
+
+    /// Start a new game, with the maze of the seed code `seed` or a random one, and
+    /// the size and algorithm of the URL. A paused game stays paused.
+    pub fn restart(&self, seed: Option<String>) -> Result<(), JsValue> {
+        let code = match seed {
+            Some(seed) => seed
+                .parse::<SeedCode>()
+                .map_err(|error| JsValue::from_str(&error.to_string()))?,
+            None => random_seed_code(),
+        };
+        let mut app = self.app.borrow_mut();
+        let paused = is_paused(&app);
+        let Some(BoardResource(mut board)) = app
+            .world_mut()
+            .remove_non_send_resource::<BoardResource<CanvasBoard>>()
+        else {
+            return Err(JsValue::from_str("The game has no board"));
+        };
+        board.seed = Some(code.to_seed());
+        *app = build_app(board, self.maze_config);
+        if paused {
+            app.world_mut().resource_mut::<Time<Virtual>>().pause();
+        }
+        Ok(())
+    }
+
+    /// Seed code of the current maze, e.g. `7KQ2-M9XD`.
+    pub fn seed(&self) -> String {
+        self.seed_code()
+            .map(|code| code.to_string())
+            .unwrap_or_default()
+    }
+
+    /// URL of this page that starts the current maze.
+    pub fn share_link(&self) -> Result<String, JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+        UrlParams {
+            seed: self.seed_code(),
+            maze: self.maze_config,
+        }
+        .share_link(&window)
+    }
+
+    /// Copy `share_link()` to the clipboard; the promise settles when it is copied.
+    pub fn copy_share_link(&self) -> Result<js_sys::Promise, JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+        let link = self.share_link()?;
+        Ok(window.navigator().clipboard().write_text(&link))
+    }
+
+    pub fn coins_left(&self) -> u32 {
+        self.hud_state().coins_left
+    }
+
+    pub fn level(&self) -> u32 {
+        self.hud_state().level
+    }
+
+    pub fn dynamites(&self) -> u32 {
+        self.hud_state().dynamites
+    }
+
+    /// Teleport charge; the teleport is ready at 100.
+    pub fn teleport_charge(&self) -> u32 {
+        self.hud_state().teleport_countdown
+    }
+
+    /// The ghost's tile as `[x, y]`, with row 0 at the top of the maze.
+    pub fn position(&self) -> Vec<i32> {
+        let app = self.app.borrow();
+        let world = app.world();
+        let (Some(maze_res), Some(player)) = (
+            world.get_resource::<MazeResource>(),
+            world.get_resource::<PlayerPosition>(),
+        ) else {
+            return Vec::new();
+        };
+        let maze = &maze_res.maze;
+        let (left, bottom, _right, _top) = maze.playable_bounds();
+        vec![
+            (player.x as i32 - left).div_euclid(maze.tile_width as i32),
+            (player.y as i32 - bottom).div_euclid(maze.tile_height as i32),
+        ]
+    }
+
+    pub fn move_up(&self) {
+        self.queue(|input| input.press(0, -1));
+    }
+
+    pub fn move_down(&self) {
+        self.queue(|input| input.press(0, 1));
+    }
+
+    pub fn move_left(&self) {
+        self.queue(|input| input.press(-1, 0));
+    }
+
+    pub fn move_right(&self) {
+        self.queue(|input| input.press(1, 0));
+    }
+
+    pub fn teleport(&self) {
+        self.queue(|input| input.action(PlayerAction::Teleport));
+    }
+
+    pub fn place_dynamite(&self) {
+        self.queue(|input| input.action(PlayerAction::PlaceDynamite));
+    }
+}
+
+impl SpookyMazeWasm {
+    fn queue(&self, queue: impl FnOnce(&mut QueuedInput)) {
+        let mut app = self.app.borrow_mut();
+        if !is_paused(&app) {
+            queue(&mut app.world_mut().resource_mut::<QueuedInput>());
+        }
+    }
+
+    fn seed_code(&self) -> Option<SeedCode> {
+        let app = self.app.borrow();
+        app.world()
+            .resource::<MazeSeed>()
+            .0
+            .as_ref()
+            .and_then(SeedCode::from_seed)
+    }
+
+    fn hud_state(&self) -> HudState {
+        self.app.borrow().world().resource::<HudState>().clone()
+    }
+}
+
+/// The game on `board`, with the page's input and event systems.
+fn build_app(board: CanvasBoard, maze_config: MazeConfig) -> App {
+    let mut app = board::build_app(
+        board,
+        GameConfig {
+            game_step: Duration::from_millis(GAME_STEP_MS),
+            ..GameConfig::default()
+        },
+    );
+    app.insert_resource(maze_config)
+        .init_resource::<QueuedInput>()
+        .init_resource::<GameEvents>()
+        .add_systems(FixedUpdate, dispatch_queued_input.in_set(TickSet::Input))
+        .add_systems(Update, collect_game_events);
+    app
+}
+
+fn is_paused(app: &App) -> bool {
+    app.world().resource::<Time<Virtual>>().is_paused()
+}
+
+/// A new seed code from the browser's random source.
+fn random_seed_code() -> SeedCode {
+    let mut bytes = [0u8; 8];
+    getrandom::getrandom(&mut bytes).expect("no random source");
+    SeedCode::random(u64::from_le_bytes(bytes))
+}
+
+fn request_animation_frame(callback: &Closure<dyn FnMut()>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    if let Err(error) = window.request_animation_frame(callback.as_ref().unchecked_ref()) {
+        console::error_2(&"Cannot request an animation frame:".into(), &error);
+    }
+}
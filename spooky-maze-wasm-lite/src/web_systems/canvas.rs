@@ -0,0 +1,140 @@
+use core::convert::Infallible;
+use core::time::Duration;
+
+use embedded_graphics::pixelcolor::Rgb888;
+use embedded_graphics::prelude::*;
+use spooky_core::board::Board;
+use spooky_core::render::pixel_format::Direct;
+use wasm_bindgen::{Clamped, JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData, Performance};
+
+/// An RGBA copy of the canvas. `dirty::flush` draws the changed regions into it, and
+/// `present` puts it on the canvas in one `putImageData` call.
+pub struct CanvasDisplay {
+    context: CanvasRenderingContext2d,
+    size: Size,
+    rgba: Vec<u8>,
+    changed: bool,
+}
+
+impl CanvasDisplay {
+    /// The display takes the size the canvas element has.
+    pub fn new(canvas: &HtmlCanvasElement) -> Result<Self, JsValue> {
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("Canvas has no 2D context"))?
+            .dyn_into::<CanvasRenderingContext2d>()?;
+        let size = Size::new(canvas.width(), canvas.height());
+        let mut rgba = vec![0; (size.width * size.height * 4) as usize];
+        for pixel in rgba.chunks_exact_mut(4) {
+            pixel[3] = 255;
+        }
+        Ok(Self {
+            context,
+            size,
+            rgba,
+            changed: true,
+        })
+    }
+
+    /// Put the buffer on the canvas if anything was drawn since the last call.
+    pub fn present(&mut self) -> Result<(), JsValue> {
+        if !self.changed {
+            return Ok(());
+        }
+        let image = ImageData::new_with_u8_clamped_array_and_sh(
+            Clamped(&self.rgba),
+            self.size.width,
+            self.size.height,
+        )?;
+        self.context.put_image_data(&image, 0.0, 0.0)?;
+        self.changed = false;
+        Ok(())
+    }
+}
+
+impl OriginDimensions for CanvasDisplay {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for CanvasDisplay {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let width = self.size.width as i32;
+        let height = self.size.height as i32;
+        for Pixel(point, color) in pixels {
+            if point.x < 0 || point.y < 0 || point.x >= width || point.y >= height {
+                continue;
+            }
+            let index = (point.y * width + point.x) as usize * 4;
+            self.rgba[index..index + 3].copy_from_slice(&[color.r(), color.g(), color.b()]);
+            self.changed = true;
+        }
+        Ok(())
+    }
+}
+
+/// The browser as a board: a canvas as the display, `performance.now()` as the clock
+/// and the browser's random source for the seed. Keys and buttons do not go through
+/// the board but through `QueuedInput`.
+pub struct CanvasBoard {
+    pub display: CanvasDisplay,
+    /// Seed of the next game; a random one is used when it is `None`.
+    pub seed: Option<[u8; 32]>,
+    performance: Performance,
+}
+
+impl CanvasBoard {
+    pub fn new(canvas: &HtmlCanvasElement) -> Result<Self, JsValue> {
+        let performance = web_sys::window()
+            .and_then(|window| window.performance())
+            .ok_or_else(|| JsValue::from_str("No performance timer"))?;
+        Ok(Self {
+            display: CanvasDisplay::new(canvas)?,
+            seed: None,
+            performance,
+        })
+    }
+}
+
+impl Board for CanvasBoard {
+    type Display = CanvasDisplay;
+    type Palette = Direct;
+
+    fn display_size(&self) -> Size {
+        self.display.size()
+    }
+
+    fn display(&mut self) -> &mut Self::Display {
+        &mut self.display
+    }
+
+    fn palette(&self) -> Direct {
+        Direct
+    }
+
+    fn seed(&mut self) -> [u8; 32] {
+        self.seed.take().unwrap_or_else(|| {
+            let mut seed = [0u8; 32];
+            getrandom::getrandom(&mut seed).expect("no random source");
+            seed
+        })
+    }
+
+    fn now(&self) -> Duration {
+        Duration::from_secs_f64(self.performance.now() / 1000.0)
+    }
+
+    fn idle(&mut self) {
+        if let Err(error) = self.display.present() {
+            web_sys::console::error_2(&"Cannot draw the frame:".into(), &error);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use spooky_core::events::coin::CoinCollisionMessage;
+use spooky_core::events::npc::NpcCollisionMessage;
+use spooky_core::systems::hud::HudState;
+
+/// Something the page may want to react to, e.g. with a sound or a dialog.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    CoinCollected {
+        coins_left: u32,
+    },
+    CaughtByNpc,
+    /// Every coin of the maze is collected.
+    LevelComplete {
+        level: u32,
+    },
+}
+
+impl GameEvent {
+    /// Name passed to the JavaScript callbacks.
+    pub fn name(self) -> &'static str {
+        match self {
+            GameEvent::CoinCollected { .. } => "coin_collected",
+            GameEvent::CaughtByNpc => "caught_by_npc",
+            GameEvent::LevelComplete { .. } => "level_complete",
+        }
+    }
+
+    /// The number that comes with the event, if any.
+    pub fn value(self) -> Option<u32> {
+        match self {
+            GameEvent::CoinCollected { coins_left } => Some(coins_left),
+            GameEvent::CaughtByNpc => None,
+            GameEvent::LevelComplete { level } => Some(level),
+        }
+    }
+}
+
+/// Events of the last frame, taken by the frame loop and passed to JavaScript once
+/// the app is no longer borrowed.
+#[derive(Resource, Default)]
+pub struct GameEvents(pub Vec<GameEvent>);
+
+pub fn collect_game_events(
+    mut events: ResMut<GameEvents>,
+    mut coins: MessageReader<CoinCollisionMessage>,
+    mut npcs: MessageReader<NpcCollisionMessage>,
+    hud_state: Res<HudState>,
+    mut completed: Local<bool>,
+) {
+    for _ in coins.read() {
+        events.0.push(GameEvent::CoinCollected {
+            coins_left: hud_state.coins_left,
+        });
+    }
+    if npcs.read().count() > 0 {
+        events.0.push(GameEvent::CaughtByNpc);
+    }
+    let complete = hud_state.coins_left == 0;
+    if complete && !*completed {
+        events.0.push(GameEvent::LevelComplete {
+            level: hud_state.level,
+        });
+    }
+    *completed = complete;
+}
@@ -0,0 +1,21 @@
+use spooky_core::events::action::PlayerAction;
+use spooky_core::systems::queued_input::QueuedInput;
+
+/// Queue the input for a `KeyboardEvent.code`. Arrows and WASD move, Space teleports
+/// and Enter places dynamite, as in the Bevy build. Returns whether the key was used,
+/// so that the page does not also scroll.
+///
+/// The canvas shows the maze like the embedded boards, with world y growing
+/// downwards, so "up" is a negative step.
+pub fn queue_key(code: &str, input: &mut QueuedInput) -> bool {
+    match code {
+        "ArrowUp" | "KeyW" => input.press(0, -1),
+        "ArrowDown" | "KeyS" => input.press(0, 1),
+        "ArrowLeft" | "KeyA" => input.press(-1, 0),
+        "ArrowRight" | "KeyD" => input.press(1, 0),
+        "Space" => input.action(PlayerAction::Teleport),
+        "Enter" | "NumpadEnter" => input.action(PlayerAction::PlaceDynamite),
+        _ => return false,
+    }
+    true
+}
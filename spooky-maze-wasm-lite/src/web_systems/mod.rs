@@ -0,0 +1,4 @@
+pub mod canvas;
+pub mod game_events;
+pub mod keyboard;
+pub mod url_params;
@@ -0,0 +1,84 @@
+use spooky_core::maze::MazeAlgorithm;
+use spooky_core::resources::MazeConfig;
+use spooky_core::seed_code::SeedCode;
+use wasm_bindgen::JsValue;
+use web_sys::{UrlSearchParams, Window, console};
+
+/// The game a page URL asks for, e.g. `?seed=7KQ2-M9XD&size=16&algo=prim`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UrlParams {
+    /// `seed`: a seed code; a random maze without it.
+    pub seed: Option<SeedCode>,
+    /// `size` in maze fields per side and `algo` as in `MazeAlgorithm::name`.
+    pub maze: MazeConfig,
+}
+
+impl UrlParams {
+    /// Read the parameters of the page URL. Invalid values are logged and the
+    /// defaults used instead, so a mistyped link still starts a game.
+    pub fn from_location(window: &Window) -> Self {
+        let search = window.location().search().unwrap_or_default();
+        let Ok(query) = UrlSearchParams::new_with_str(&search) else {
+            return Self::default();
+        };
+        let mut params = Self::default();
+        if let Some(seed) = query.get("seed") {
+            match seed.parse() {
+                Ok(code) => params.seed = Some(code),
+                Err(error) => warn("seed", &seed, &error.to_string()),
+            }
+        }
+        if let Some(size) = query.get("size") {
+            match size.parse::<u32>() {
+                Ok(fields) if (MazeConfig::MIN_SIZE..=MazeConfig::MAX_SIZE).contains(&fields) => {
+                    params.maze.width = fields;
+                    params.maze.height = fields;
+                }
+                _ => warn(
+                    "size",
+                    &size,
+                    &format!(
+                        "expected {} to {} fields",
+                        MazeConfig::MIN_SIZE,
+                        MazeConfig::MAX_SIZE
+                    ),
+                ),
+            }
+        }
+        if let Some(algo) = query.get("algo") {
+            match algo.parse::<MazeAlgorithm>() {
+                Ok(algorithm) => params.maze.algorithm = algorithm,
+                Err(error) => warn("algo", &algo, &error.to_string()),
+            }
+        }
+        params
+    }
+
+    /// Link to this page that starts the same game. `size` and `algo` are left out
+    /// when they are the defaults.
+    pub fn share_link(&self, window: &Window) -> Result<String, JsValue> {
+        let location = window.location();
+        let query = UrlSearchParams::new()?;
+        if let Some(seed) = self.seed {
+            query.append("seed", &seed.to_string());
+        }
+        let defaults = MazeConfig::default();
+        if self.maze.width != defaults.width {
+            query.append("size", &self.maze.width.to_string());
+        }
+        if self.maze.algorithm != defaults.algorithm {
+            query.append("algo", self.maze.algorithm.name());
+        }
+        let query = String::from(query.to_string());
+        let mut link = location.origin()? + &location.pathname()?;
+        if !query.is_empty() {
+            link.push('?');
+            link.push_str(&query);
+        }
+        Ok(link)
+    }
+}
+
+fn warn(name: &str, value: &str, reason: &str) {
+    console::warn_1(&format!("Ignoring URL parameter {name}={value}: {reason}").into());
+}
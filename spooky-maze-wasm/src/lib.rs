@@ -3,11 +3,15 @@ use bevy::window::{WindowPlugin, WindowResolution};
 use std::sync::{Arc, Mutex};
 use std::collections::VecDeque;
 
-use spooky_core::events::npc::NpcCollisionEvent;
-use spooky_core::events::player::PlayerInputEvent;
-use spooky_core::events::walker::WalkerCollisionEvent;
-use spooky_core::events::{coin::CoinCollisionEvent, dynamite::DynamiteCollisionEvent};
+use spooky_core::events::action::PlayerActionMessage;
+use spooky_core::events::npc::NpcCollisionMessage;
+use spooky_core::events::player::PlayerInputMessage;
+use spooky_core::events::walker::WalkerCollisionMessage;
+use spooky_core::events::{coin::CoinCollisionMessage, dynamite::DynamiteCollisionMessage};
+use spooky_core::input_buffer::InputBuffer;
 use spooky_core::resources::MazeSeed;
+use spooky_core::key_bindings::{InputAction, key_from_name};
+use spooky_core::systems::TickSet;
 use spooky_core::systems::hud::HudState;
 use spooky_core::{systems, systems::collisions};
 use wasm_bindgen::prelude::*;
@@ -16,16 +20,16 @@ use web_sys::console;
 mod wasm_input;
 use wasm_input::WasmInputPlugin;
 
-// Input queue for buffering input events
+// One-tile moves from the page buttons, handed to the `InputBuffer` every frame
 #[derive(Resource, Clone, Default)]
 pub struct InputQueue {
-    queue: Arc<Mutex<VecDeque<PlayerInputEvent>>>,
+    queue: Arc<Mutex<VecDeque<(i32, i32)>>>,
 }
 
 #[wasm_bindgen]
 pub struct SpookyMazeWasm {
     app: App,
-    input_queue: Arc<Mutex<VecDeque<PlayerInputEvent>>>,
+    input_queue: Arc<Mutex<VecDeque<(i32, i32)>>>,
 }
 
 #[wasm_bindgen]
@@ -45,7 +49,7 @@ impl SpookyMazeWasm {
             DefaultPlugins.set(WindowPlugin {
                 primary_window: Some(Window {
                     title: "Spooky Maze Game".into(),
-                    resolution: WindowResolution::new(320.0, 240.0),
+                    resolution: WindowResolution::new(320, 240),
                     canvas: Some("#gameCanvas".into()),
                     ..default()
                 }),
@@ -61,18 +65,23 @@ impl SpookyMazeWasm {
         })))
         .add_systems(Startup, systems::setup::setup)
         .insert_resource(Time::<Fixed>::from_hz(10.0))
-        .add_event::<PlayerInputEvent>()
-        .add_event::<CoinCollisionEvent>()
-        .add_event::<DynamiteCollisionEvent>()
-        .add_event::<WalkerCollisionEvent>()
-        .add_event::<NpcCollisionEvent>()
+        .add_message::<PlayerInputMessage>()
+        .add_message::<PlayerActionMessage>()
+        .add_message::<CoinCollisionMessage>()
+        .add_message::<DynamiteCollisionMessage>()
+        .add_message::<WalkerCollisionMessage>()
+        .add_message::<NpcCollisionMessage>()
         .insert_resource(HudState::default())
         .insert_resource(InputQueue { queue: input_queue.clone() })
+        .configure_sets(
+            FixedUpdate,
+            (TickSet::Input, TickSet::Record, TickSet::Logic).chain(),
+        )
         .add_systems(
             FixedUpdate,
             (
-                process_input_queue,
                 systems::process_player_input::process_player_input,
+                systems::player_action::handle_player_action,
                 collisions::coin::detect_coin_collision,
                 collisions::coin::remove_coin_on_collision,
                 collisions::dynamite::handle_dynamite_collision,
@@ -83,8 +92,10 @@ impl SpookyMazeWasm {
                 systems::dynamite_logic::handle_dynamite_collision,
                 systems::npc_logic::update_npc_movement,
                 systems::game_logic::update_game,
-            ),
-        );
+            )
+                .in_set(TickSet::Logic),
+        )
+        .add_systems(Update, (process_input_queue, systems::motion::interpolate_motion));
         
         console::log_1(&"Spooky Maze WASM initialized".into());
         
@@ -99,22 +110,22 @@ impl SpookyMazeWasm {
     
     #[wasm_bindgen]
     pub fn move_up(&mut self) {
-        self.send_input(0.0, 16.0);
+        self.send_input(0, 1);
     }
     
     #[wasm_bindgen]
     pub fn move_down(&mut self) {
-        self.send_input(0.0, -16.0);
+        self.send_input(0, -1);
     }
     
     #[wasm_bindgen]
     pub fn move_left(&mut self) {
-        self.send_input(-16.0, 0.0);
+        self.send_input(-1, 0);
     }
     
     #[wasm_bindgen]
     pub fn move_right(&mut self) {
-        self.send_input(16.0, 0.0);
+        self.send_input(1, 0);
     }
     
     #[wasm_bindgen]
@@ -131,9 +142,10 @@ impl SpookyMazeWasm {
         console::log_1(&"Place dynamite requested".into());
     }
     
-    fn send_input(&mut self, dx: f32, dy: f32) {
+    /// Queue a one-tile move; positive dy is up.
+    fn send_input(&mut self, dx: i32, dy: i32) {
         if let Ok(mut queue) = self.input_queue.lock() {
-            queue.push_back(PlayerInputEvent { dx, dy });
+            queue.push_back((dx, dy));
             console::log_1(&format!("Input queued: dx={}, dy={}", dx, dy).into());
         } else {
             console::log_1(&"Failed to lock input queue".into());
@@ -141,14 +153,31 @@ impl SpookyMazeWasm {
     }
 }
 
-// System to process input events from the queue
-fn process_input_queue(
-    input_queue: Res<InputQueue>,
-    mut player_input_events: EventWriter<PlayerInputEvent>,
-) {
+/// Bind `key` (a `KeyCode` name such as "KeyW") to a slot (0 or 1) of `action`
+/// (e.g. "move_up") and store the key map in localStorage. The new map is used the
+/// next time the game starts; a key already bound elsewhere is rejected.
+#[wasm_bindgen]
+pub fn set_key_binding(action: &str, slot: usize, key: &str) -> Result<(), JsValue> {
+    let action = InputAction::from_name(action)
+        .ok_or_else(|| JsValue::from_str(&format!("Unknown action `{}`", action)))?;
+    let key = key_from_name(key).ok_or_else(|| JsValue::from_str(&format!("Unknown key `{}`", key)))?;
+    if slot >= spooky_core::key_bindings::SLOTS_PER_ACTION {
+        return Err(JsValue::from_str("Slot must be 0 or 1"));
+    }
+    let mut bindings = wasm_input::load_key_bindings();
+    bindings
+        .rebind(action, slot, key)
+        .map_err(|conflict| JsValue::from_str(&conflict.to_string()))?;
+    wasm_input::save_key_bindings(&bindings);
+    Ok(())
+}
+
+// Hands the button presses to the `InputBuffer`, which replays them one per tick
+// together with the keyboard
+fn process_input_queue(input_queue: Res<InputQueue>, mut buffer: ResMut<InputBuffer>) {
     if let Ok(mut queue) = input_queue.queue.lock() {
-        while let Some(event) = queue.pop_front() {
-            player_input_events.write(event);
+        while let Some((dx, dy)) = queue.pop_front() {
+            buffer.press(dx, dy);
         }
     }
 }
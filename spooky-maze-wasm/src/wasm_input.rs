@@ -1,50 +1,141 @@
-use bevy::prelude::*;
 use bevy::input::keyboard::KeyCode;
-use spooky_core::events::player::PlayerInputEvent;
+use bevy::prelude::*;
+use spooky_core::events::action::{PlayerAction, PlayerActionMessage};
+use spooky_core::events::player::PlayerInputMessage;
+use spooky_core::input_buffer::{InputBuffer, InputRepeatConfig};
+use spooky_core::key_bindings::{InputAction, KeyBindings};
+use spooky_core::resources::MazeResource;
+use spooky_core::systems::TickSet;
 use web_sys::console;
 
+/// localStorage key holding the key map in the `action = Key, Key` text format.
+pub const KEY_BINDINGS_STORAGE_KEY: &str = "spooky-maze.key-bindings";
+
+/// Movement actions and the one-tile direction they stand for.
+const MOVE_ACTIONS: [(InputAction, i32, i32); 4] = [
+    (InputAction::MoveUp, 0, 1),
+    (InputAction::MoveDown, 0, -1),
+    (InputAction::MoveLeft, -1, 0),
+    (InputAction::MoveRight, 1, 0),
+];
+
 pub struct WasmInputPlugin;
 
 impl Plugin for WasmInputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, dispatch_keyboard_input);
+        app.insert_resource(load_key_bindings())
+            .init_resource::<InputRepeatConfig>()
+            .init_resource::<InputBuffer>()
+            .add_systems(
+                Update,
+                (
+                    record_keyboard_input,
+                    dispatch_keyboard_actions,
+                    persist_key_bindings,
+                ),
+            )
+            .add_systems(FixedUpdate, dispatch_buffered_input.in_set(TickSet::Input));
+    }
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Load the key map from localStorage, falling back to the defaults.
+pub fn load_key_bindings() -> KeyBindings {
+    let stored = local_storage().and_then(|storage| {
+        storage
+            .get_item(KEY_BINDINGS_STORAGE_KEY)
+            .ok()
+            .flatten()
+    });
+    match stored.map(|text| KeyBindings::from_config_str(&text)) {
+        Some(Ok(bindings)) => bindings,
+        Some(Err(err)) => {
+            console::log_1(&format!("Ignoring stored key bindings: {}", err).into());
+            KeyBindings::default()
+        }
+        None => KeyBindings::default(),
+    }
+}
+
+/// Store the key map in localStorage.
+pub fn save_key_bindings(bindings: &KeyBindings) {
+    if let Some(storage) = local_storage() {
+        if storage
+            .set_item(KEY_BINDINGS_STORAGE_KEY, &bindings.to_config_string())
+            .is_err()
+        {
+            console::log_1(&"Failed to save key bindings".into());
+        }
+    }
+}
+
+fn persist_key_bindings(key_bindings: Res<KeyBindings>) {
+    if key_bindings.is_changed() && !key_bindings.is_added() {
+        save_key_bindings(&key_bindings);
     }
 }
 
-fn dispatch_keyboard_input(
+/// Records the movement keys from the `KeyBindings` map into the `InputBuffer` every
+/// frame, so that quick taps between two fixed ticks are not lost.
+fn record_keyboard_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut player_input_events: EventWriter<PlayerInputEvent>,
+    key_bindings: Res<KeyBindings>,
+    config: Res<InputRepeatConfig>,
+    time: Res<Time<Virtual>>,
+    mut buffer: ResMut<InputBuffer>,
 ) {
-    let mut dx = 0.0;
-    let mut dy = 0.0;
-    let step = 16.0; // adjust to your tile size
-    
-    if keyboard_input.pressed(KeyCode::ArrowUp) || keyboard_input.pressed(KeyCode::KeyW) {
-        dy += step;
-    }
-    if keyboard_input.pressed(KeyCode::ArrowDown) || keyboard_input.pressed(KeyCode::KeyS) {
-        dy -= step;
-    }
-    if keyboard_input.pressed(KeyCode::ArrowLeft) || keyboard_input.pressed(KeyCode::KeyA) {
-        dx -= step;
-    }
-    if keyboard_input.pressed(KeyCode::ArrowRight) || keyboard_input.pressed(KeyCode::KeyD) {
-        dx += step;
-    }
-    
-    if dx != 0.0 || dy != 0.0 {
-        console::log_1(&format!("Input: dx={}, dy={}", dx, dy).into());
-        player_input_events.write(PlayerInputEvent { dx, dy });
-    }
-    
-    // Handle special actions
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        console::log_1(&"Space pressed - teleport".into());
-        // TODO: Send teleport event
-    }
-    
-    if keyboard_input.just_pressed(KeyCode::Enter) {
-        console::log_1(&"Enter pressed - place dynamite".into());
-        // TODO: Send place dynamite event
+    let mut held_x = 0;
+    let mut held_y = 0;
+    for (action, dx, dy) in MOVE_ACTIONS {
+        if key_bindings.just_pressed(&keyboard_input, action) {
+            buffer.press(dx, dy);
+        }
+        if key_bindings.pressed(&keyboard_input, action) {
+            held_x += dx;
+            held_y += dy;
+        }
+    }
+    buffer.set_held(held_x, held_y, time.elapsed_secs(), &config);
+}
+
+/// Replays the buffered keys and page buttons on the fixed tick: one
+/// `PlayerInputMessage` of one tile per tick. A positive dy moves up.
+fn dispatch_buffered_input(
+    config: Res<InputRepeatConfig>,
+    time: Res<Time<Virtual>>,
+    maze_res: Res<MazeResource>,
+    mut buffer: ResMut<InputBuffer>,
+    mut player_input_writer: MessageWriter<PlayerInputMessage>,
+) {
+    if let Some((dx, dy)) = buffer.next_move(time.elapsed_secs(), &config) {
+        player_input_writer.write(PlayerInputMessage {
+            dx: dx as f32 * maze_res.maze.tile_width as f32,
+            dy: dy as f32 * maze_res.maze.tile_height as f32,
+        });
+    }
+}
+
+/// Reads the teleport and dynamite keys. Runs in `Update` so that short presses are
+/// not lost between fixed ticks.
+fn dispatch_keyboard_actions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut player_action_writer: MessageWriter<PlayerActionMessage>,
+) {
+    if key_bindings.just_pressed(&keyboard_input, InputAction::Teleport) {
+        console::log_1(&"Teleport pressed".into());
+        player_action_writer.write(PlayerActionMessage {
+            action: PlayerAction::Teleport,
+        });
+    }
+
+    if key_bindings.just_pressed(&keyboard_input, InputAction::PlaceDynamite) {
+        console::log_1(&"Place dynamite pressed".into());
+        player_action_writer.write(PlayerActionMessage {
+            action: PlayerAction::PlaceDynamite,
+        });
     }
 }